@@ -0,0 +1,96 @@
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::sim::Sim;
+
+pub const HOURS_PER_DAY: u64 = 24;
+
+// the colony wakes up at 06:00 on day 1
+const START_HOUR: u64 = 6;
+
+const DAWN: (f32, f32) = (5.0, 7.0);
+const DUSK: (f32, f32) = (18.0, 21.0);
+const NIGHT_TINT: Color = Color::srgba(0.02, 0.03, 0.12, 0.55);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClockTime {
+    pub day: u64,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+impl ClockTime {
+    pub fn at(ticks: u64, ticks_per_hour: u32) -> Self {
+        let per_hour = ticks_per_hour as u64;
+        let total = ticks + START_HOUR * per_hour;
+        ClockTime {
+            day: total / (HOURS_PER_DAY * per_hour) + 1,
+            hour: ((total / per_hour) % HOURS_PER_DAY) as u32,
+            minute: ((total % per_hour) * 60 / per_hour) as u32,
+        }
+    }
+
+    pub fn is_night(&self) -> bool {
+        let hour = self.hour as f32;
+        hour < DAWN.0 || hour >= DUSK.1
+    }
+
+    pub fn describe(&self) -> String {
+        format!("Day {}, {:02}:{:02}", self.day, self.hour, self.minute)
+    }
+}
+
+/// Sent once for every in-game hour that starts, for anything that runs on a
+/// daily schedule.
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HourChanged {
+    pub day: u64,
+    pub hour: u32,
+}
+
+/// Marks the sprite that darkens the map at night.
+#[derive(Component)]
+pub struct DaylightOverlay;
+
+pub fn spawn_daylight_overlay(commands: &mut Commands, config: &GameConfig) {
+    let size = Vec2::new(config.map_w as f32, config.map_h as f32) * config.tile_size;
+    commands.spawn((
+        Sprite {
+            color: Color::NONE,
+            custom_size: Some(size),
+            ..default()
+        },
+        Transform::from_xyz(0.0, 0.0, 5.0),
+        DaylightOverlay,
+    ));
+}
+
+pub fn update_daylight_overlay(
+    sim: Res<Sim>,
+    config: Res<GameConfig>,
+    mut q: Query<&mut Sprite, With<DaylightOverlay>>,
+) {
+    if !sim.is_changed() {
+        return;
+    }
+
+    let time = ClockTime::at(sim.ticks, config.ticks_per_hour);
+    let hour = time.hour as f32 + time.minute as f32 / 60.0;
+    for mut sprite in &mut q {
+        sprite.color = daylight_tint(hour);
+    }
+}
+
+pub fn daylight_tint(hour: f32) -> Color {
+    let darkness = if hour < DAWN.0 || hour >= DUSK.1 {
+        1.0
+    } else if hour < DAWN.1 {
+        1.0 - (hour - DAWN.0) / (DAWN.1 - DAWN.0)
+    } else if hour >= DUSK.0 {
+        (hour - DUSK.0) / (DUSK.1 - DUSK.0)
+    } else {
+        0.0
+    };
+
+    NIGHT_TINT.with_alpha(NIGHT_TINT.alpha() * darkness)
+}
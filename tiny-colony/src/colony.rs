@@ -1,6 +1,46 @@
 use bevy::prelude::*;
 
-#[derive(Resource, Debug, Default)]
-pub struct Colony {
-    pub wood: u32,
+use crate::pawn::Inventory;
+
+/// Everything the colony keeps count of in its stockpile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Item {
+    Wood,
+    Stone,
+    Planks,
+    Blocks,
+    /// What trade is paid in.
+    Silver,
+    // only to be had from traders
+    Medicine,
+    Steel,
 }
+
+impl Item {
+    pub const ALL: [Item; 7] = [
+        Item::Wood,
+        Item::Stone,
+        Item::Planks,
+        Item::Blocks,
+        Item::Silver,
+        Item::Medicine,
+        Item::Steel,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Item::Wood => "wood",
+            Item::Stone => "stone",
+            Item::Planks => "planks",
+            Item::Blocks => "stone blocks",
+            Item::Silver => "silver",
+            Item::Medicine => "medicine",
+            Item::Steel => "steel",
+        }
+    }
+}
+
+/// The stockpile. It counts items the same way a pawn's pack does, so the
+/// per-item bookkeeping lives on [`Inventory`] alone.
+#[derive(Resource, Debug, Default, Deref, DerefMut)]
+pub struct Colony(pub Inventory);
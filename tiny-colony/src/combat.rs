@@ -0,0 +1,289 @@
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::creatures::{Creature, CreatureKind, Wildlife};
+use crate::health::Health;
+use crate::pawn::{Inventory, Pawn, Task};
+use crate::rng::SimRng;
+use crate::world::{self, Path, WorldMap};
+
+const PAWN_DAMAGE: u32 = 10;
+const PAWN_ATTACK_TICKS: u32 = 5;
+
+/// Pawns notice hostiles this close and stop working to deal with them.
+const THREAT_RADIUS: i32 = 6;
+/// Allies and enemies this close to a fight count towards the odds.
+const SUPPORT_RADIUS: i32 = 3;
+
+const NEIGHBOURS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Melee {
+    pub damage: u32,
+    /// Ticks between blows.
+    pub cooldown: u32,
+    pub ready_in: u32,
+}
+
+impl Melee {
+    pub fn new(damage: u32, cooldown: u32) -> Self {
+        Melee {
+            damage,
+            cooldown,
+            ready_in: 0,
+        }
+    }
+
+    pub fn pawn() -> Self {
+        Melee::new(PAWN_DAMAGE, PAWN_ATTACK_TICKS)
+    }
+}
+
+/// What a pawn is doing about nearby hostiles. Anything but `Work` suspends
+/// its task until the threat is gone.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Stance {
+    #[default]
+    Work,
+    Fight(Entity),
+    Flee(Entity),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Casualty {
+    Pawn {
+        entity: Entity,
+        id: u32,
+    },
+    Creature {
+        entity: Entity,
+        kind: CreatureKind,
+        at: IVec2,
+    },
+}
+
+#[derive(SystemParam)]
+pub struct Combatants<'w, 's> {
+    pub creatures: Query<'w, 's, (Entity, &'static mut Creature)>,
+    pub fighters: Query<'w, 's, (&'static mut Health, &'static mut Melee)>,
+    pub stances: Query<'w, 's, &'static mut Stance>,
+    pub wildlife: ResMut<'w, Wildlife>,
+}
+
+impl Combatants<'_, '_> {
+    pub fn is_dead(&self, entity: Entity) -> bool {
+        self.fighters.get(entity).is_ok_and(|(h, _)| h.is_dead())
+    }
+
+    /// Pawns that are busy with a threat or dead sit out the work phase.
+    pub fn is_engaged(&self, entity: Entity) -> bool {
+        self.stances.get(entity).is_ok_and(|s| *s != Stance::Work) || self.is_dead(entity)
+    }
+}
+
+/// One tick of combat: pawns pick fight or flight, then every creature moves
+/// and attacks. Runs one combatant at a time in id order so outcomes repeat.
+pub fn fight(
+    pawns: &mut Query<(Entity, &mut Pawn, &mut Task, &mut Inventory)>,
+    combat: &mut Combatants,
+    map: &WorldMap,
+    config: &GameConfig,
+    rng: &mut SimRng,
+) -> Vec<Casualty> {
+    let mut casualties = Vec::new();
+
+    for (_, mut melee) in &mut combat.fighters {
+        melee.ready_in = melee.ready_in.saturating_sub(1);
+    }
+
+    let mut hostiles: Vec<(u32, Entity, IVec2)> = combat
+        .creatures
+        .iter()
+        .filter(|(entity, c)| c.kind.is_hostile() && !combat.is_dead(*entity))
+        .map(|(entity, c)| (c.id, entity, c.pos()))
+        .collect();
+    hostiles.sort_by_key(|&(id, _, _)| id);
+
+    let mut order: Vec<(u32, Entity)> = pawns
+        .iter()
+        .filter(|(entity, ..)| !combat.is_dead(*entity))
+        .map(|(entity, pawn, ..)| (pawn.id, entity))
+        .collect();
+    order.sort();
+    let allies: Vec<IVec2> = order
+        .iter()
+        .filter_map(|&(_, entity)| pawns.get(entity).ok())
+        .map(|(_, pawn, ..)| IVec2::new(pawn.x, pawn.y))
+        .collect();
+
+    for (_, entity) in order {
+        if combat.is_dead(entity) {
+            continue;
+        }
+        let Ok((_, mut pawn, ..)) = pawns.get_mut(entity) else {
+            continue;
+        };
+        let pos = IVec2::new(pawn.x, pawn.y);
+
+        let threat = hostiles
+            .iter()
+            .filter(|(_, e, _)| !combat.is_dead(*e))
+            .map(|&(_, e, at)| (distance(pos, at), e, at))
+            .filter(|&(dist, ..)| dist <= THREAT_RADIUS)
+            .min_by_key(|&(dist, _, at)| (dist, at.y, at.x));
+
+        let stance = match threat {
+            None => Stance::Work,
+            Some((_, enemy, at)) => {
+                let health = combat.fighters.get(entity).map(|(h, _)| *h);
+                let healthy = health.is_ok_and(|h| !h.needs_rest());
+                let friends = allies
+                    .iter()
+                    .filter(|&&a| distance(a, pos) <= SUPPORT_RADIUS)
+                    .count();
+                let enemies = hostiles
+                    .iter()
+                    .filter(|(_, e, a)| !combat.is_dead(*e) && distance(*a, at) <= SUPPORT_RADIUS)
+                    .count();
+                if healthy && friends >= enemies {
+                    Stance::Fight(enemy)
+                } else {
+                    Stance::Flee(enemy)
+                }
+            }
+        };
+        if let Ok(mut current) = combat.stances.get_mut(entity) {
+            current.set_if_neq(stance);
+        }
+
+        match (stance, threat) {
+            (Stance::Fight(enemy), Some((dist, _, at))) => {
+                if dist > 1 {
+                    let next = step_towards(&mut pawn.path, map, pos, at);
+                    (pawn.x, pawn.y) = (next.x, next.y);
+                } else if strike(combat, entity, enemy)
+                    && let Ok((_, creature)) = combat.creatures.get(enemy)
+                {
+                    casualties.push(Casualty::Creature {
+                        entity: enemy,
+                        kind: creature.kind,
+                        at,
+                    });
+                }
+            }
+            (Stance::Flee(_), Some((_, _, at))) => {
+                let next = step_away(map, pos, at);
+                (pawn.x, pawn.y) = (next.x, next.y);
+            }
+            _ => {}
+        }
+    }
+
+    let mut order: Vec<(u32, Entity)> = combat
+        .creatures
+        .iter()
+        .filter(|(entity, _)| !combat.is_dead(*entity))
+        .map(|(entity, c)| (c.id, entity))
+        .collect();
+    order.sort();
+
+    for (_, entity) in order {
+        let Ok((_, creature)) = combat.creatures.get(entity) else {
+            continue;
+        };
+        let (kind, pos) = (creature.kind, creature.pos());
+
+        let target = if kind.is_hostile() {
+            pawns
+                .iter()
+                .filter(|(e, ..)| !combat.is_dead(*e))
+                .map(|(e, pawn, ..)| (distance(pos, IVec2::new(pawn.x, pawn.y)), pawn.id, e))
+                .filter(|&(dist, ..)| dist <= kind.aggro_radius())
+                .min_by_key(|&(dist, id, _)| (dist, id))
+        } else {
+            None
+        };
+
+        if let Some((dist, id, victim)) = target
+            && dist <= 1
+        {
+            if strike(combat, entity, victim) {
+                casualties.push(Casualty::Pawn { entity: victim, id });
+            }
+            continue;
+        }
+
+        let Ok((_, mut creature)) = combat.creatures.get_mut(entity) else {
+            continue;
+        };
+        creature.effort += kind.speed();
+        if creature.effort < 1.0 {
+            continue;
+        }
+        creature.effort -= 1.0;
+
+        let next = match target {
+            Some((_, _, victim)) => {
+                let (_, pawn, ..) = pawns.get(victim).unwrap();
+                step_towards(&mut creature.path, map, pos, IVec2::new(pawn.x, pawn.y))
+            }
+            None if kind == CreatureKind::Raider => {
+                step_towards(&mut creature.path, map, pos, config.stockpile())
+            }
+            None => {
+                let next = pos + NEIGHBOURS[rng.below(4) as usize];
+                if world::is_walkable(map, next) {
+                    next
+                } else {
+                    pos
+                }
+            }
+        };
+        (creature.x, creature.y) = (next.x, next.y);
+    }
+
+    for casualty in &casualties {
+        if let Casualty::Creature { kind, .. } = casualty
+            && *kind != CreatureKind::Raider
+        {
+            combat.wildlife.animals = combat.wildlife.animals.saturating_sub(1);
+        }
+    }
+
+    casualties
+}
+
+/// Lands a blow if the attacker is ready. Returns true if it killed.
+fn strike(combat: &mut Combatants, attacker: Entity, target: Entity) -> bool {
+    let damage = match combat.fighters.get_mut(attacker) {
+        Ok((_, mut melee)) if melee.ready_in == 0 && melee.damage > 0 => {
+            melee.ready_in = melee.cooldown;
+            melee.damage
+        }
+        _ => return false,
+    };
+
+    match combat.fighters.get_mut(target) {
+        Ok((mut health, _)) => health.damage(damage),
+        Err(_) => false,
+    }
+}
+
+fn distance(a: IVec2, b: IVec2) -> i32 {
+    (a - b).abs().element_sum()
+}
+
+fn step_towards(path: &mut Path, map: &WorldMap, from: IVec2, to: IVec2) -> IVec2 {
+    path.next_step(map, from, to).unwrap_or(from)
+}
+
+fn step_away(map: &WorldMap, from: IVec2, threat: IVec2) -> IVec2 {
+    NEIGHBOURS
+        .into_iter()
+        .map(|offset| from + offset)
+        .filter(|&next| world::is_walkable(map, next))
+        .max_by_key(|&next| (distance(next, threat), -next.y, -next.x))
+        .filter(|&next| distance(next, threat) > distance(from, threat))
+        .unwrap_or(from)
+}
@@ -1,11 +1,303 @@
-pub const MAP_W: i32 = 64;
-pub const MAP_H: i32 = 64;
+use std::fmt;
 
-pub const TILE_SIZE: f32 = 12.0;
-pub const TILE_GAP: f32 = 1.0;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-pub const STOCKPILE_X: i32 = MAP_W / 2;
-pub const STOCKPILE_Y: i32 = MAP_H / 2;
+pub const CONFIG_PATH: &str = "config.ron";
+pub const KEY_BINDINGS_PATH: &str = "keybindings.ron";
 
-pub const PAWN_COUNT: usize = 1000;
-pub const PAWN_RADIUS_PX: u32 = 12;
+const MAX_MAP_SIZE: i32 = 4096;
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfig {
+    pub map_w: i32,
+    pub map_h: i32,
+
+    pub tile_size: f32,
+    pub tile_gap: f32,
+
+    /// Left unset, the stockpile goes in the middle of the map.
+    pub stockpile_x: Option<i32>,
+    pub stockpile_y: Option<i32>,
+
+    pub pawn_count: usize,
+    pub pawn_radius_px: u32,
+
+    pub tick_hz: f32,
+    pub max_ticks_per_frame: u32,
+    pub ticks_per_hour: u32,
+    pub idle_alert_ticks: u32,
+    pub reservation_timeout_ticks: u32,
+
+    pub seed: u64,
+    pub days_per_season: u32,
+    pub weather_hours: u32,
+    pub tree_growth_chance: f32,
+    pub fire_chance: f32,
+    pub fire_spread_chance: f32,
+
+    pub animal_count: usize,
+    pub wolf_chance: f32,
+    pub raid_every_hours: u32,
+    pub raid_size: u32,
+
+    pub hunger_ticks: u32,
+    pub accident_chance: f32,
+
+    pub beds: usize,
+    pub join_wealth: u32,
+    pub wanderer_every_hours: u32,
+
+    pub caravan_every_hours: u32,
+
+    pub metrics_path: Option<String>,
+    pub metrics_every: u32,
+    /// Runs this many ticks without a window, then quits.
+    pub headless_ticks: Option<u32>,
+
+    pub scenario: Option<String>,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig {
+            map_w: 64,
+            map_h: 64,
+            tile_size: 12.0,
+            tile_gap: 1.0,
+            stockpile_x: None,
+            stockpile_y: None,
+            pawn_count: 1000,
+            pawn_radius_px: 12,
+            tick_hz: 10.0,
+            max_ticks_per_frame: 32,
+            ticks_per_hour: 10,
+            idle_alert_ticks: 50,
+            reservation_timeout_ticks: 1000,
+            seed: 1,
+            days_per_season: 5,
+            weather_hours: 6,
+            tree_growth_chance: 0.2,
+            fire_chance: 0.0005,
+            fire_spread_chance: 0.05,
+            animal_count: 6,
+            wolf_chance: 0.3,
+            raid_every_hours: 48,
+            raid_size: 3,
+            hunger_ticks: 2000,
+            accident_chance: 0.01,
+            beds: 1100,
+            join_wealth: 200,
+            wanderer_every_hours: 12,
+            caravan_every_hours: 36,
+            metrics_path: None,
+            metrics_every: 1,
+            headless_ticks: None,
+            scenario: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io { path: String, err: std::io::Error },
+    Parse { path: String, err: String },
+    UnknownOption(String),
+    MissingValue(String),
+    BadValue { option: String, value: String },
+    Invalid { field: &'static str, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io { path, err } => write!(f, "failed to read {path}: {err}"),
+            ConfigError::Parse { path, err } => write!(f, "failed to parse {path}: {err}"),
+            ConfigError::UnknownOption(option) => write!(f, "unknown option '{option}'"),
+            ConfigError::MissingValue(option) => write!(f, "missing value for '{option}'"),
+            ConfigError::BadValue { option, value } => {
+                write!(f, "invalid value '{value}' for '{option}'")
+            }
+            ConfigError::Invalid { field, reason } => write!(f, "invalid {field}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl GameConfig {
+    pub fn stockpile(&self) -> IVec2 {
+        IVec2::new(
+            self.stockpile_x.unwrap_or(self.map_w / 2),
+            self.stockpile_y.unwrap_or(self.map_h / 2),
+        )
+    }
+
+    pub fn tick_seconds(&self) -> f32 {
+        1.0 / self.tick_hz
+    }
+
+    /// Loads `--config <path>` (or `config.ron` if present), then applies the
+    /// remaining `--option value` pairs on top and validates the result.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, ConfigError> {
+        let args: Vec<String> = args.into_iter().collect();
+
+        let mut path = None;
+        let mut overrides = Vec::new();
+        let mut iter = args.iter();
+        while let Some(option) = iter.next() {
+            let value = iter
+                .next()
+                .ok_or_else(|| ConfigError::MissingValue(option.clone()))?;
+            if option == "--config" {
+                path = Some(value.clone());
+            } else {
+                overrides.push((option, value));
+            }
+        }
+
+        let mut config = match path {
+            Some(path) => GameConfig::load(&path)?,
+            None if std::path::Path::new(CONFIG_PATH).exists() => GameConfig::load(CONFIG_PATH)?,
+            None => GameConfig::default(),
+        };
+
+        for (option, value) in overrides {
+            config.set_option(option, value)?;
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|err| ConfigError::Io {
+            path: path.to_string(),
+            err,
+        })?;
+
+        ron::from_str(&contents).map_err(|err| ConfigError::Parse {
+            path: path.to_string(),
+            err: err.to_string(),
+        })
+    }
+
+    pub fn set_option(&mut self, option: &str, value: &str) -> Result<(), ConfigError> {
+        let name = option
+            .strip_prefix("--")
+            .ok_or_else(|| ConfigError::UnknownOption(option.to_string()))?;
+
+        match name {
+            "map-w" => self.map_w = parse_option(option, value)?,
+            "map-h" => self.map_h = parse_option(option, value)?,
+            "tile-size" => self.tile_size = parse_option(option, value)?,
+            "tile-gap" => self.tile_gap = parse_option(option, value)?,
+            "stockpile-x" => self.stockpile_x = Some(parse_option(option, value)?),
+            "stockpile-y" => self.stockpile_y = Some(parse_option(option, value)?),
+            "pawn-count" => self.pawn_count = parse_option(option, value)?,
+            "pawn-radius-px" => self.pawn_radius_px = parse_option(option, value)?,
+            "tick-hz" => self.tick_hz = parse_option(option, value)?,
+            "max-ticks-per-frame" => self.max_ticks_per_frame = parse_option(option, value)?,
+            "ticks-per-hour" => self.ticks_per_hour = parse_option(option, value)?,
+            "idle-alert-ticks" => self.idle_alert_ticks = parse_option(option, value)?,
+            "reservation-timeout-ticks" => {
+                self.reservation_timeout_ticks = parse_option(option, value)?
+            }
+            "seed" => self.seed = parse_option(option, value)?,
+            "days-per-season" => self.days_per_season = parse_option(option, value)?,
+            "weather-hours" => self.weather_hours = parse_option(option, value)?,
+            "tree-growth-chance" => self.tree_growth_chance = parse_option(option, value)?,
+            "fire-chance" => self.fire_chance = parse_option(option, value)?,
+            "fire-spread-chance" => self.fire_spread_chance = parse_option(option, value)?,
+            "animal-count" => self.animal_count = parse_option(option, value)?,
+            "wolf-chance" => self.wolf_chance = parse_option(option, value)?,
+            "raid-every-hours" => self.raid_every_hours = parse_option(option, value)?,
+            "raid-size" => self.raid_size = parse_option(option, value)?,
+            "hunger-ticks" => self.hunger_ticks = parse_option(option, value)?,
+            "accident-chance" => self.accident_chance = parse_option(option, value)?,
+            "beds" => self.beds = parse_option(option, value)?,
+            "join-wealth" => self.join_wealth = parse_option(option, value)?,
+            "wanderer-every-hours" => self.wanderer_every_hours = parse_option(option, value)?,
+            "caravan-every-hours" => self.caravan_every_hours = parse_option(option, value)?,
+            "metrics-path" => self.metrics_path = Some(value.to_string()),
+            "metrics-every" => self.metrics_every = parse_option(option, value)?,
+            "headless-ticks" => self.headless_ticks = Some(parse_option(option, value)?),
+            "scenario" => self.scenario = Some(value.to_string()),
+            _ => return Err(ConfigError::UnknownOption(option.to_string())),
+        }
+
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if !(1..=MAX_MAP_SIZE).contains(&self.map_w) {
+            return Err(invalid("map_w", format!("must be between 1 and {MAX_MAP_SIZE}")));
+        }
+        if !(1..=MAX_MAP_SIZE).contains(&self.map_h) {
+            return Err(invalid("map_h", format!("must be between 1 and {MAX_MAP_SIZE}")));
+        }
+        if !self.tile_size.is_finite() || self.tile_size <= 0.0 {
+            return Err(invalid("tile_size", "must be greater than 0"));
+        }
+        if !(0.0..self.tile_size).contains(&self.tile_gap) {
+            return Err(invalid("tile_gap", "must be at least 0 and less than tile_size"));
+        }
+        let stockpile = self.stockpile();
+        if !(0..self.map_w).contains(&stockpile.x) {
+            return Err(invalid("stockpile_x", "must be inside the map"));
+        }
+        if !(0..self.map_h).contains(&stockpile.y) {
+            return Err(invalid("stockpile_y", "must be inside the map"));
+        }
+        if self.pawn_radius_px == 0 {
+            return Err(invalid("pawn_radius_px", "must be greater than 0"));
+        }
+        if !self.tick_hz.is_finite() || self.tick_hz <= 0.0 {
+            return Err(invalid("tick_hz", "must be greater than 0"));
+        }
+        if self.max_ticks_per_frame == 0 {
+            return Err(invalid("max_ticks_per_frame", "must be greater than 0"));
+        }
+        if self.ticks_per_hour == 0 {
+            return Err(invalid("ticks_per_hour", "must be greater than 0"));
+        }
+        if self.days_per_season == 0 {
+            return Err(invalid("days_per_season", "must be greater than 0"));
+        }
+        if !(0.0..=1.0).contains(&self.tree_growth_chance) {
+            return Err(invalid("tree_growth_chance", "must be between 0 and 1"));
+        }
+        if !(0.0..=1.0).contains(&self.fire_chance) {
+            return Err(invalid("fire_chance", "must be between 0 and 1"));
+        }
+        if !(0.0..=1.0).contains(&self.fire_spread_chance) {
+            return Err(invalid("fire_spread_chance", "must be between 0 and 1"));
+        }
+        if !(0.0..=1.0).contains(&self.wolf_chance) {
+            return Err(invalid("wolf_chance", "must be between 0 and 1"));
+        }
+        if !(0.0..=1.0).contains(&self.accident_chance) {
+            return Err(invalid("accident_chance", "must be between 0 and 1"));
+        }
+        if self.metrics_every == 0 {
+            return Err(invalid("metrics_every", "must be greater than 0"));
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_option<T: std::str::FromStr>(option: &str, value: &str) -> Result<T, ConfigError> {
+    value.parse().map_err(|_| ConfigError::BadValue {
+        option: option.to_string(),
+        value: value.to_string(),
+    })
+}
+
+fn invalid(field: &'static str, reason: impl Into<String>) -> ConfigError {
+    ConfigError::Invalid {
+        field,
+        reason: reason.into(),
+    }
+}
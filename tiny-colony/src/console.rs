@@ -0,0 +1,403 @@
+use std::collections::VecDeque;
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+use crate::colony::Item;
+use crate::config::GameConfig;
+use crate::crafting::{self, BenchKind, Bill};
+use crate::events::ColonyEvent;
+use crate::fire;
+use crate::input::{Action, Binding, KeyBindings, Rebinding};
+use crate::pawn::{self, Pawn};
+use crate::population;
+use crate::sim::{MAX_SPEED, Sim, TickState};
+use crate::trade::{self, Caravan};
+use crate::ui::SelectedPawn;
+use crate::world::{self, Tile};
+
+const CONSOLE_CAPACITY: usize = 100;
+
+const HELP_LINES: [&str; 12] = [
+    "spawn_pawns <n> [at <x> <y>]",
+    "set_tile <x> <y> <ground|tree|stockpile|burnt|sand|mud|rock|shallow_water|deep_water>",
+    "ignite <x> <y>",
+    "mine <x> <y>",
+    "build <sawmill|stonecutter|bed> <x> <y>",
+    "bill <x> <y> <make <n>|until <n>|clear>",
+    "give <wood|stone> <n>",
+    "trade <buy|sell> <item> <n>",
+    "tick <n>",
+    "select <pawn id>",
+    "speed <multiplier>",
+    "help",
+];
+
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    pub output: VecDeque<String>,
+}
+
+impl Console {
+    pub fn print(&mut self, line: impl Into<String>) {
+        if self.output.len() >= CONSOLE_CAPACITY {
+            self.output.pop_front();
+        }
+        self.output.push_back(line.into());
+    }
+}
+
+#[derive(Message, Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleCommand {
+    SpawnPawns { count: usize, at: Option<IVec2> },
+    SetTile { at: IVec2, tile: Tile },
+    Ignite(IVec2),
+    Mine(IVec2),
+    Build { kind: BenchKind, at: IVec2 },
+    BuildBed(IVec2),
+    SetBill { at: IVec2, bill: Option<Bill> },
+    GiveWood(u32),
+    GiveStone(u32),
+    Trade { buy: bool, item: Item, amount: u32 },
+    Tick(u32),
+    Select(u32),
+    Speed(f32),
+    Help,
+}
+
+pub fn parse_command(line: &str) -> Result<ConsoleCommand, String> {
+    let args: Vec<&str> = line.split_whitespace().collect();
+
+    match args.as_slice() {
+        ["spawn_pawns", count] => Ok(ConsoleCommand::SpawnPawns {
+            count: parse_arg(count, "count")?,
+            at: None,
+        }),
+        ["spawn_pawns", count, "at", x, y] => Ok(ConsoleCommand::SpawnPawns {
+            count: parse_arg(count, "count")?,
+            at: Some(parse_tile_pos(x, y)?),
+        }),
+        ["set_tile", x, y, tile] => Ok(ConsoleCommand::SetTile {
+            at: parse_tile_pos(x, y)?,
+            tile: parse_tile(tile)?,
+        }),
+        ["ignite", x, y] => Ok(ConsoleCommand::Ignite(parse_tile_pos(x, y)?)),
+        ["mine", x, y] => Ok(ConsoleCommand::Mine(parse_tile_pos(x, y)?)),
+        ["build", "bed", x, y] => Ok(ConsoleCommand::BuildBed(parse_tile_pos(x, y)?)),
+        ["build", kind, x, y] => Ok(ConsoleCommand::Build {
+            kind: parse_bench(kind)?,
+            at: parse_tile_pos(x, y)?,
+        }),
+        ["bill", x, y, "make", count] => {
+            let count = parse_arg(count, "count")?;
+            if count > 0 {
+                Ok(ConsoleCommand::SetBill {
+                    at: parse_tile_pos(x, y)?,
+                    bill: Some(Bill::Make(count)),
+                })
+            } else {
+                Err("count must be greater than 0".to_string())
+            }
+        }
+        ["bill", x, y, "until", target] => Ok(ConsoleCommand::SetBill {
+            at: parse_tile_pos(x, y)?,
+            bill: Some(Bill::Until(parse_arg(target, "target")?)),
+        }),
+        ["bill", x, y, "clear"] => Ok(ConsoleCommand::SetBill {
+            at: parse_tile_pos(x, y)?,
+            bill: None,
+        }),
+        ["give", "wood", amount] => Ok(ConsoleCommand::GiveWood(parse_arg(amount, "amount")?)),
+        ["give", "stone", amount] => Ok(ConsoleCommand::GiveStone(parse_arg(amount, "amount")?)),
+        ["give", item, _] => Err(format!("unknown item '{item}'")),
+        ["trade", side @ ("buy" | "sell"), item, amount] => Ok(ConsoleCommand::Trade {
+            buy: *side == "buy",
+            item: parse_item(item)?,
+            amount: parse_arg(amount, "amount")?,
+        }),
+        ["tick", count] => Ok(ConsoleCommand::Tick(parse_arg(count, "count")?)),
+        ["select", id] => Ok(ConsoleCommand::Select(parse_arg(id, "pawn id")?)),
+        ["speed", speed] => {
+            let speed: f32 = parse_arg(speed, "speed")?;
+            // also turns away inf and NaN, which the sim timer can't take
+            if speed > 0.0 && speed <= MAX_SPEED {
+                Ok(ConsoleCommand::Speed(speed))
+            } else {
+                Err(format!("speed must be greater than 0 and at most {MAX_SPEED}"))
+            }
+        }
+        ["help"] => Ok(ConsoleCommand::Help),
+        [] => Err("empty command".to_string()),
+        [name, ..] => Err(format!("unknown or malformed command '{name}' (try 'help')")),
+    }
+}
+
+fn parse_arg<T: std::str::FromStr>(value: &str, name: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid {name} '{value}'"))
+}
+
+fn parse_tile_pos(x: &str, y: &str) -> Result<IVec2, String> {
+    Ok(IVec2::new(parse_arg(x, "x")?, parse_arg(y, "y")?))
+}
+
+fn parse_tile(name: &str) -> Result<Tile, String> {
+    match name {
+        "ground" => Ok(Tile::Ground),
+        "tree" => Ok(Tile::Tree),
+        "stockpile" => Ok(Tile::Stockpile),
+        "burnt" => Ok(Tile::Burnt),
+        "shallow_water" => Ok(Tile::ShallowWater),
+        "deep_water" => Ok(Tile::DeepWater),
+        "rock" => Ok(Tile::Rock),
+        "sand" => Ok(Tile::Sand),
+        "mud" => Ok(Tile::Mud),
+        _ => Err(format!("unknown tile '{name}'")),
+    }
+}
+
+fn parse_bench(name: &str) -> Result<BenchKind, String> {
+    match name {
+        "sawmill" => Ok(BenchKind::Sawmill),
+        "stonecutter" => Ok(BenchKind::Stonecutter),
+        _ => Err(format!("unknown workbench '{name}'")),
+    }
+}
+
+fn parse_item(name: &str) -> Result<Item, String> {
+    match name {
+        "wood" => Ok(Item::Wood),
+        "stone" => Ok(Item::Stone),
+        "planks" => Ok(Item::Planks),
+        "blocks" => Ok(Item::Blocks),
+        "medicine" => Ok(Item::Medicine),
+        "steel" => Ok(Item::Steel),
+        _ => Err(format!("unknown item '{name}'")),
+    }
+}
+
+pub fn console_input(
+    mut console: ResMut<Console>,
+    bindings: Res<KeyBindings>,
+    rebinding: Res<Rebinding>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut keyboard: MessageReader<KeyboardInput>,
+    mut commands_out: MessageWriter<ConsoleCommand>,
+) {
+    let toggle = bindings.get(Action::ToggleConsole);
+
+    // the toggle may have been moved onto a mouse button
+    if let Some(Binding::Mouse(button)) = toggle
+        && buttons.just_pressed(button)
+        && rebinding.waiting.is_none()
+    {
+        console.open = !console.open;
+    }
+
+    for input in keyboard.read() {
+        if input.state != ButtonState::Pressed || rebinding.waiting.is_some() {
+            continue;
+        }
+
+        if toggle == Some(Binding::Key(input.key_code)) {
+            console.open = !console.open;
+            continue;
+        }
+
+        if !console.open {
+            continue;
+        }
+
+        match &input.logical_key {
+            Key::Enter => {
+                let line = std::mem::take(&mut console.input);
+                if line.trim().is_empty() {
+                    continue;
+                }
+                console.print(format!("> {line}"));
+                match parse_command(&line) {
+                    Ok(command) => {
+                        commands_out.write(command);
+                    }
+                    Err(err) => console.print(err),
+                }
+            }
+            Key::Backspace => {
+                console.input.pop();
+            }
+            Key::Escape => console.open = false,
+            _ => {
+                if let Some(text) = &input.text {
+                    console
+                        .input
+                        .extend(text.chars().filter(|c| !c.is_control()));
+                }
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn run_console_commands(
+    mut commands: Commands,
+    mut requests: MessageReader<ConsoleCommand>,
+    mut console: ResMut<Console>,
+    config: Res<GameConfig>,
+    mut state: TickState,
+    mut sim: ResMut<Sim>,
+    mut selected: ResMut<SelectedPawn>,
+    q_pawns: Query<(Entity, &Pawn)>,
+    mut caravans: Query<&mut Caravan>,
+) {
+    let state = &mut state;
+    for request in requests.read() {
+        match *request {
+            ConsoleCommand::SpawnPawns { count, at } => {
+                let at = at.unwrap_or(config.stockpile());
+                if !world::in_bounds(&state.map, at.x, at.y) {
+                    console.print(format!("({},{}) is outside the map", at.x, at.y));
+                    continue;
+                }
+                let mut occupied: HashSet<IVec2> = q_pawns
+                    .iter()
+                    .map(|(_, pawn)| IVec2::new(pawn.x, pawn.y))
+                    .collect();
+                let spawned = pawn::spawn_pawns_at(
+                    &mut commands,
+                    &state.map,
+                    at,
+                    count,
+                    &mut state.pawn_ids,
+                    &mut occupied,
+                );
+                console.print(format!("Spawned {spawned} pawns at ({},{})", at.x, at.y));
+            }
+            ConsoleCommand::SetTile { at, tile } => {
+                if !world::in_bounds(&state.map, at.x, at.y) {
+                    console.print(format!("({},{}) is outside the map", at.x, at.y));
+                    continue;
+                }
+                world::set(&mut state.map, at.x, at.y, tile);
+                if tile == Tile::Tree {
+                    state.world_trees.0.insert(at);
+                } else {
+                    state.world_trees.0.remove(&at);
+                }
+                if tile != Tile::Rock {
+                    state.designations.0.remove(&at);
+                }
+                state.benches.0.remove(&at);
+                console.print(format!("Set ({},{}) to {tile:?}", at.x, at.y));
+            }
+            ConsoleCommand::Ignite(at) => {
+                if !fire::ignite(&mut state.map, &mut state.world_trees, &mut state.fires, at) {
+                    console.print(format!("No tree to set alight at ({},{})", at.x, at.y));
+                    continue;
+                }
+                // frees the tile for firefighters; the woodcutter gives up once it sees the flames
+                if let Some(&pawn) = state.reservations.reserved_tiles.get(&at) {
+                    state.reservations.release_pawn(pawn);
+                }
+                state.events.write(ColonyEvent::FireStarted { at });
+                console.print(format!("Set ({},{}) alight", at.x, at.y));
+            }
+            ConsoleCommand::Mine(at) => {
+                if !world::in_bounds(&state.map, at.x, at.y)
+                    || world::get(&state.map, at.x, at.y) != Tile::Rock
+                {
+                    console.print(format!("No rock to mine at ({},{})", at.x, at.y));
+                    continue;
+                }
+                state.designations.0.insert(at);
+                console.print(format!("Marked ({},{}) for mining", at.x, at.y));
+            }
+            ConsoleCommand::Build { kind, at } => {
+                match crafting::place(
+                    &mut state.map,
+                    &mut state.benches,
+                    &mut state.colony,
+                    kind,
+                    at,
+                ) {
+                    Ok(()) => {
+                        console.print(format!("Built a {} at ({},{})", kind.name(), at.x, at.y));
+                    }
+                    Err(err) => console.print(format!("Can't build there: {err}")),
+                }
+            }
+            ConsoleCommand::BuildBed(at) => {
+                match population::build_bed(&mut state.map, &mut state.colony, at) {
+                    Ok(()) => console.print(format!("Built a bed at ({},{})", at.x, at.y)),
+                    Err(err) => console.print(format!("Can't build there: {err}")),
+                }
+            }
+            ConsoleCommand::SetBill { at, bill } => {
+                let Some(bench) = state.benches.0.get_mut(&at) else {
+                    console.print(format!("No workbench at ({},{})", at.x, at.y));
+                    continue;
+                };
+                bench.bill = bill;
+                console.print(format!("Bill set: {}", bench.describe()));
+            }
+            ConsoleCommand::GiveWood(amount) => {
+                state.colony.wood = state.colony.wood.saturating_add(amount);
+                console.print(format!("Gave {amount} wood"));
+            }
+            ConsoleCommand::GiveStone(amount) => {
+                state.colony.stone = state.colony.stone.saturating_add(amount);
+                console.print(format!("Gave {amount} stone"));
+            }
+            ConsoleCommand::Trade { buy, item, amount } => {
+                let Some(mut caravan) = caravans.iter_mut().find(|c| c.is_trading()) else {
+                    console.print("No caravan is here to trade with");
+                    continue;
+                };
+                let result = if buy {
+                    trade::buy(&mut caravan, &mut state.colony, item, amount)
+                } else {
+                    trade::sell(&mut caravan, &mut state.colony, item, amount)
+                };
+                match result {
+                    Ok(silver) => {
+                        let event = ColonyEvent::Traded {
+                            bought: buy,
+                            item,
+                            amount,
+                            silver,
+                        };
+                        console.print(event.describe());
+                        state.events.write(event);
+                    }
+                    Err(err) => console.print(format!("No deal: {err}")),
+                }
+            }
+            ConsoleCommand::Tick(count) => {
+                sim.pending_ticks = sim.pending_ticks.saturating_add(count);
+                console.print(format!("Queued {count} ticks"));
+            }
+            ConsoleCommand::Select(id) => {
+                match q_pawns.iter().find(|(_, pawn)| pawn.id == id) {
+                    Some((entity, _)) => {
+                        selected.0 = Some(entity);
+                        console.print(format!("Selected pawn {id}"));
+                    }
+                    None => console.print(format!("No pawn with id {id}")),
+                }
+            }
+            ConsoleCommand::Speed(speed) => {
+                sim.speed = speed;
+                console.print(format!("Sim speed set to {speed}x"));
+            }
+            ConsoleCommand::Help => {
+                for line in HELP_LINES {
+                    console.print(line);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::colony::{Colony, Item};
+use crate::world::{self, Tile, WorldMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchKind {
+    Sawmill,
+    Stonecutter,
+}
+
+/// One batch of work at a bench: what goes in and what comes out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Recipe {
+    pub input: Item,
+    pub input_amount: u32,
+    pub output: Item,
+    pub output_amount: u32,
+    pub work_ticks: u8,
+}
+
+impl BenchKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            BenchKind::Sawmill => "sawmill",
+            BenchKind::Stonecutter => "stonecutter",
+        }
+    }
+
+    pub fn recipe(self) -> Recipe {
+        match self {
+            BenchKind::Sawmill => Recipe {
+                input: Item::Wood,
+                input_amount: 1,
+                output: Item::Planks,
+                output_amount: 2,
+                work_ticks: 15,
+            },
+            BenchKind::Stonecutter => Recipe {
+                input: Item::Stone,
+                input_amount: 2,
+                output: Item::Blocks,
+                output_amount: 1,
+                work_ticks: 20,
+            },
+        }
+    }
+
+    /// Paid out of the stockpile when the bench is placed.
+    pub fn cost(self) -> [(Item, u32); 2] {
+        match self {
+            BenchKind::Sawmill => [(Item::Wood, 10), (Item::Stone, 5)],
+            // the saw blades are steel, which only caravans sell
+            BenchKind::Stonecutter => [(Item::Stone, 10), (Item::Steel, 2)],
+        }
+    }
+}
+
+/// A standing production order on a bench.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bill {
+    /// Make this many more batches, then stop.
+    Make(u32),
+    /// Keep going while the stockpile holds fewer than this many of the product.
+    Until(u32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Workbench {
+    pub kind: BenchKind,
+    pub bill: Option<Bill>,
+}
+
+impl Workbench {
+    pub fn new(kind: BenchKind) -> Self {
+        Workbench { kind, bill: None }
+    }
+
+    /// Whether the bill calls for another batch and the stockpile holds the
+    /// inputs for it.
+    pub fn wants_work(&self, colony: &Colony) -> bool {
+        let recipe = self.kind.recipe();
+        let wanted = match self.bill {
+            None => false,
+            Some(Bill::Make(count)) => count > 0,
+            Some(Bill::Until(target)) => colony.amount(recipe.output) < target,
+        };
+        wanted && colony.amount(recipe.input) >= recipe.input_amount
+    }
+
+    /// Counts a finished batch against the bill. A `Make` bill is done with
+    /// once the last batch is in.
+    pub fn finish_batch(&mut self) {
+        if let Some(Bill::Make(count)) = self.bill {
+            self.bill = (count > 1).then_some(Bill::Make(count - 1));
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let output = self.kind.recipe().output.name();
+        match self.bill {
+            None => format!("{}, no bill", self.kind.name()),
+            Some(Bill::Make(count)) => format!("{}, make {count} more", self.kind.name()),
+            Some(Bill::Until(target)) => {
+                format!("{}, make until {target} {output}", self.kind.name())
+            }
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Workbenches(pub HashMap<IVec2, Workbench>);
+
+/// Builds a bench on open ground, paying for it out of the stockpile.
+pub fn place(
+    map: &mut WorldMap,
+    benches: &mut Workbenches,
+    colony: &mut Colony,
+    kind: BenchKind,
+    at: IVec2,
+) -> Result<(), String> {
+    if !world::in_bounds(map, at.x, at.y) {
+        return Err(format!("({},{}) is outside the map", at.x, at.y));
+    }
+    if world::get(map, at.x, at.y) != Tile::Ground {
+        return Err(format!("({},{}) isn't clear ground", at.x, at.y));
+    }
+    for (item, amount) in kind.cost() {
+        if colony.amount(item) < amount {
+            return Err(format!("a {} needs {amount} {}", kind.name(), item.name()));
+        }
+    }
+
+    for (item, amount) in kind.cost() {
+        *colony.amount_mut(item) -= amount;
+    }
+    world::set(map, at.x, at.y, Tile::Workbench);
+    benches.0.insert(at, Workbench::new(kind));
+    Ok(())
+}
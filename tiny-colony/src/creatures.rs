@@ -0,0 +1,214 @@
+use bevy::prelude::*;
+
+use crate::combat::Melee;
+use crate::config::GameConfig;
+use crate::health::Health;
+use crate::rng::SimRng;
+use crate::world::{self, Path, Tile, WorldMap};
+
+const DEER_COLOR: Color = Color::srgb(0.72, 0.55, 0.35);
+const WOLF_COLOR: Color = Color::srgb(0.55, 0.55, 0.6);
+const RAIDER_COLOR: Color = Color::srgb(0.85, 0.15, 0.15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreatureKind {
+    Deer,
+    Wolf,
+    Raider,
+}
+
+impl CreatureKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            CreatureKind::Deer => "deer",
+            CreatureKind::Wolf => "wolf",
+            CreatureKind::Raider => "raider",
+        }
+    }
+
+    pub fn is_hostile(&self) -> bool {
+        !matches!(self, CreatureKind::Deer)
+    }
+
+    pub fn max_health(&self) -> u32 {
+        match self {
+            CreatureKind::Deer => 40,
+            CreatureKind::Wolf => 50,
+            CreatureKind::Raider => 80,
+        }
+    }
+
+    fn melee(&self) -> Melee {
+        match self {
+            CreatureKind::Deer => Melee::new(0, 1),
+            CreatureKind::Wolf => Melee::new(8, 5),
+            CreatureKind::Raider => Melee::new(12, 6),
+        }
+    }
+
+    /// How close a pawn has to be before this creature goes for it.
+    pub fn aggro_radius(&self) -> i32 {
+        match self {
+            CreatureKind::Deer => 0,
+            CreatureKind::Wolf => 6,
+            CreatureKind::Raider => 8,
+        }
+    }
+
+    /// Tiles moved per tick.
+    pub fn speed(&self) -> f32 {
+        match self {
+            CreatureKind::Deer => 0.3,
+            CreatureKind::Wolf => 1.0,
+            CreatureKind::Raider => 0.75,
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            CreatureKind::Deer => DEER_COLOR,
+            CreatureKind::Wolf => WOLF_COLOR,
+            CreatureKind::Raider => RAIDER_COLOR,
+        }
+    }
+}
+
+/// An animal or raider. Lives on the same grid as pawns.
+#[derive(Component)]
+pub struct Creature {
+    pub id: u32,
+    pub kind: CreatureKind,
+    pub x: i32,
+    pub y: i32,
+    pub effort: f32,
+    pub path: Path,
+}
+
+impl Creature {
+    pub fn pos(&self) -> IVec2 {
+        IVec2::new(self.x, self.y)
+    }
+}
+
+/// Ids handed out to creatures and how many animals are roaming, so the map
+/// can be topped back up as they die.
+#[derive(Resource, Default)]
+pub struct Wildlife {
+    pub next_id: u32,
+    pub animals: usize,
+}
+
+pub fn spawn_creature(
+    commands: &mut Commands,
+    wildlife: &mut Wildlife,
+    kind: CreatureKind,
+    at: IVec2,
+) -> Entity {
+    let id = wildlife.next_id;
+    wildlife.next_id += 1;
+    if kind != CreatureKind::Raider {
+        wildlife.animals += 1;
+    }
+
+    commands
+        .spawn((
+            Creature {
+                id,
+                kind,
+                x: at.x,
+                y: at.y,
+                effort: 0.0,
+                path: Path::default(),
+            },
+            Health::new(kind.max_health()),
+            kind.melee(),
+        ))
+        .id()
+}
+
+/// Runs once per in-game hour: brings in a new animal while the map is short
+/// of them, and a raiding party when one is due.
+pub fn spawn_arrivals(
+    commands: &mut Commands,
+    wildlife: &mut Wildlife,
+    map: &WorldMap,
+    config: &GameConfig,
+    rng: &mut SimRng,
+    hour: u64,
+) -> Option<(usize, IVec2)> {
+    if wildlife.animals < config.animal_count
+        && let Some(at) = edge_tile(map, rng)
+    {
+        let kind = if rng.chance(config.wolf_chance) {
+            CreatureKind::Wolf
+        } else {
+            CreatureKind::Deer
+        };
+        spawn_creature(commands, wildlife, kind, at);
+    }
+
+    if config.raid_every_hours == 0 || !hour.is_multiple_of(config.raid_every_hours as u64) {
+        return None;
+    }
+
+    let at = edge_tile(map, rng)?;
+    let mut spawned = 0;
+    for offset in 0..config.raid_size as i32 {
+        // line up along whichever edge they came in on
+        let step = if at.x == 0 || at.x == map.width - 1 {
+            IVec2::new(0, offset)
+        } else {
+            IVec2::new(offset, 0)
+        };
+        let pos = at + step;
+        if world::in_bounds(map, pos.x, pos.y) {
+            spawn_creature(commands, wildlife, CreatureKind::Raider, pos);
+            spawned += 1;
+        }
+    }
+    Some((spawned, at))
+}
+
+pub fn edge_tile(map: &WorldMap, rng: &mut SimRng) -> Option<IVec2> {
+    // a handful of tries is plenty unless the whole border is forest
+    for _ in 0..16 {
+        let along = rng.below((map.width + map.height) as u32) as i32;
+        let far = rng.chance(0.5);
+        let at = if along < map.width {
+            IVec2::new(along, if far { map.height - 1 } else { 0 })
+        } else {
+            IVec2::new(if far { map.width - 1 } else { 0 }, along - map.width)
+        };
+        if matches!(world::get(map, at.x, at.y), Tile::Ground | Tile::Burnt) {
+            return Some(at);
+        }
+    }
+    None
+}
+
+pub fn attach_creature_sprites(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    q: Query<(Entity, &Creature), Added<Creature>>,
+) {
+    for (entity, creature) in &q {
+        commands.entity(entity).insert((
+            Sprite {
+                color: creature.kind.color(),
+                custom_size: Some(Vec2::splat(config.tile_size * 0.7)),
+                ..default()
+            },
+            Transform::default(),
+        ));
+    }
+}
+
+pub fn update_creature_sprites(
+    config: Res<GameConfig>,
+    mut q: Query<(&Creature, &mut Transform), Changed<Creature>>,
+) {
+    for (creature, mut transform) in &mut q {
+        let pos = world::grid_to_world(&config, creature.x, creature.y);
+        transform.translation = pos.truncate().extend(2.0);
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::colony::Item;
+use crate::creatures::CreatureKind;
+use crate::health::Harm;
+use crate::sim::Sim;
+
+const EVENT_LOG_CAPACITY: usize = 200;
+
+#[derive(Message, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColonyEvent {
+    TreeFelled { pawn: u32, at: IVec2 },
+    WoodDelivered { pawn: u32, amount: u32 },
+    RockMined { pawn: u32, at: IVec2 },
+    ItemsDelivered { pawn: u32, item: Item, amount: u32 },
+    ItemsCrafted { pawn: u32, item: Item, amount: u32 },
+    PawnIdleTooLong { pawn: u32, ticks: u32 },
+    NoTreesLeft,
+    FireStarted { at: IVec2 },
+    FireExtinguished { pawn: u32, at: IVec2 },
+    RaidArrived { size: usize, at: IVec2 },
+    PawnInjured { pawn: u32, damage: u32 },
+    PawnTreated { pawn: u32 },
+    PawnDied { pawn: u32, cause: Harm },
+    CreatureKilled { kind: CreatureKind, at: IVec2 },
+    PawnJoined { pawn: u32, at: IVec2 },
+    WandererTurnedAway,
+    CaravanArrived { at: IVec2 },
+    CaravanOpenForTrade,
+    CaravanLeft,
+    Traded { bought: bool, item: Item, amount: u32, silver: u32 },
+}
+
+impl ColonyEvent {
+    pub fn is_alert(&self) -> bool {
+        matches!(
+            self,
+            ColonyEvent::NoTreesLeft
+                | ColonyEvent::FireStarted { .. }
+                | ColonyEvent::RaidArrived { .. }
+                | ColonyEvent::PawnDied { .. }
+                | ColonyEvent::CaravanOpenForTrade
+        )
+    }
+
+    pub fn describe(&self) -> String {
+        match *self {
+            ColonyEvent::TreeFelled { pawn, at } => {
+                format!("Pawn {pawn} felled a tree at ({},{})", at.x, at.y)
+            }
+            ColonyEvent::WoodDelivered { pawn, amount } => {
+                format!("Pawn {pawn} delivered {amount} wood")
+            }
+            ColonyEvent::RockMined { pawn, at } => {
+                format!("Pawn {pawn} mined rock at ({},{})", at.x, at.y)
+            }
+            ColonyEvent::ItemsDelivered { pawn, item, amount } => {
+                format!("Pawn {pawn} delivered {amount} {}", item.name())
+            }
+            ColonyEvent::ItemsCrafted { pawn, item, amount } => {
+                format!("Pawn {pawn} made {amount} {}", item.name())
+            }
+            ColonyEvent::PawnIdleTooLong { pawn, ticks } => {
+                format!("Pawn {pawn} has been idle for {ticks} ticks")
+            }
+            ColonyEvent::NoTreesLeft => "Forest depleted".to_string(),
+            ColonyEvent::FireStarted { at } => format!("Fire broke out at ({},{})", at.x, at.y),
+            ColonyEvent::FireExtinguished { pawn, at } => {
+                format!("Pawn {pawn} put out the fire at ({},{})", at.x, at.y)
+            }
+            ColonyEvent::RaidArrived { size, at } => {
+                format!("{size} raiders arrived at ({},{})", at.x, at.y)
+            }
+            ColonyEvent::PawnInjured { pawn, damage } => {
+                format!("Pawn {pawn} was hurt in a work accident (-{damage} health)")
+            }
+            ColonyEvent::PawnTreated { pawn } => {
+                format!("Pawn {pawn} was given medicine for their wounds")
+            }
+            ColonyEvent::PawnDied { pawn, cause } => {
+                format!("Pawn {pawn} died {}", cause.describe())
+            }
+            ColonyEvent::CreatureKilled { kind, at } => {
+                format!("A {} was killed at ({},{})", kind.name(), at.x, at.y)
+            }
+            ColonyEvent::PawnJoined { pawn, at } => {
+                format!("A wanderer joined the colony as pawn {pawn} at ({},{})", at.x, at.y)
+            }
+            ColonyEvent::WandererTurnedAway => {
+                "A wanderer was turned away: no free beds".to_string()
+            }
+            ColonyEvent::CaravanArrived { at } => {
+                format!("A trade caravan appeared at ({},{})", at.x, at.y)
+            }
+            ColonyEvent::CaravanOpenForTrade => {
+                "A trade caravan has reached the stockpile".to_string()
+            }
+            ColonyEvent::CaravanLeft => "The trade caravan moved on".to_string(),
+            ColonyEvent::Traded {
+                bought,
+                item,
+                amount,
+                silver,
+            } => {
+                let verb = if bought { "Bought" } else { "Sold" };
+                format!("{verb} {amount} {} for {silver} silver", item.name())
+            }
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct EventLog {
+    pub entries: VecDeque<String>,
+    pub scroll: usize,
+}
+
+impl EventLog {
+    pub fn push(&mut self, entry: String) {
+        if self.entries.len() >= EVENT_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
+
+pub fn record_events(
+    sim: Res<Sim>,
+    mut log: ResMut<EventLog>,
+    mut events: MessageReader<ColonyEvent>,
+) {
+    for event in events.read() {
+        log.push(format!("[{}] {}", sim.ticks, event.describe()));
+    }
+}
@@ -0,0 +1,126 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::rng::SimRng;
+use crate::world::{self, Tile, WorldMap, WorldTrees};
+
+/// How long a tree burns before it is reduced to burnt ground.
+pub const BURN_TICKS: u32 = 30;
+
+const NEIGHBOURS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// Burning tiles and the ticks each has left before it burns out.
+#[derive(Resource, Default)]
+pub struct Fires {
+    pub burning: HashMap<IVec2, u32>,
+}
+
+impl Fires {
+    pub fn is_burning(&self, at: IVec2) -> bool {
+        self.burning.contains_key(&at)
+    }
+}
+
+/// Tiles whose fire state changed during one tick of `spread`.
+#[derive(Debug, Default)]
+pub struct FireUpdate {
+    pub ignited: Vec<IVec2>,
+    pub burnt_out: Vec<IVec2>,
+}
+
+/// Sets the tree at `at` alight. Anything that isn't a standing tree won't burn.
+pub fn ignite(
+    map: &mut WorldMap,
+    world_trees: &mut WorldTrees,
+    fires: &mut Fires,
+    at: IVec2,
+) -> bool {
+    if !world::in_bounds(map, at.x, at.y) || world::get(map, at.x, at.y) != Tile::Tree {
+        return false;
+    }
+
+    world::set(map, at.x, at.y, Tile::Burning);
+    world_trees.0.remove(&at);
+    fires.burning.insert(at, BURN_TICKS);
+    true
+}
+
+/// Puts out the fire at `at`. The tree is lost either way.
+pub fn extinguish(map: &mut WorldMap, fires: &mut Fires, at: IVec2) -> bool {
+    if fires.burning.remove(&at).is_none() {
+        return false;
+    }
+
+    world::set(map, at.x, at.y, Tile::Burnt);
+    true
+}
+
+/// Lights a random tree with probability `chance`.
+pub fn random_ignition(
+    map: &mut WorldMap,
+    world_trees: &mut WorldTrees,
+    fires: &mut Fires,
+    rng: &mut SimRng,
+    chance: f32,
+) -> Option<IVec2> {
+    if !rng.chance(chance) {
+        return None;
+    }
+
+    let at = IVec2::new(
+        rng.below(map.width as u32) as i32,
+        rng.below(map.height as u32) as i32,
+    );
+    ignite(map, world_trees, fires, at).then_some(at)
+}
+
+/// Burns every fire down by a tick, giving each neighbouring tree `chance` to
+/// catch.
+pub fn spread(
+    map: &mut WorldMap,
+    world_trees: &mut WorldTrees,
+    fires: &mut Fires,
+    rng: &mut SimRng,
+    chance: f32,
+) -> FireUpdate {
+    let mut update = FireUpdate::default();
+    if fires.burning.is_empty() {
+        return update;
+    }
+
+    // walk the fires in a fixed order so the same seed burns the same way
+    let mut burning: Vec<IVec2> = fires.burning.keys().copied().collect();
+    burning.sort_by_key(|at| (at.y, at.x));
+
+    for at in burning {
+        // the tile was overwritten some other way, e.g. from the console
+        if world::get(map, at.x, at.y) != Tile::Burning {
+            fires.burning.remove(&at);
+            continue;
+        }
+
+        for offset in NEIGHBOURS {
+            let next = at + offset;
+            if world::in_bounds(map, next.x, next.y)
+                && world::get(map, next.x, next.y) == Tile::Tree
+                && rng.chance(chance)
+            {
+                update.ignited.push(next);
+            }
+        }
+
+        let left = fires.burning.get_mut(&at).unwrap();
+        *left -= 1;
+        if *left == 0 {
+            fires.burning.remove(&at);
+            world::set(map, at.x, at.y, Tile::Burnt);
+            update.burnt_out.push(at);
+        }
+    }
+
+    // new fires only start spreading next tick
+    update
+        .ignited
+        .retain(|&at| ignite(map, world_trees, fires, at));
+    update
+}
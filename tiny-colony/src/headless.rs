@@ -0,0 +1,65 @@
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::pawn::{self, NextPawnId};
+use crate::scenario::Scenario;
+use crate::sim::{Sim, SimSet};
+use crate::world::{self, WorldTrees};
+
+/// Runs the sim without a window or any drawing: sets up the map and pawns,
+/// queues `--headless-ticks` ticks, and quits once they've gone through or a
+/// scenario has ended.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameConfig>()
+            .init_resource::<NextPawnId>()
+            .add_systems(Startup, setup_headless)
+            .add_systems(
+                Update,
+                (
+                    queue_headless_ticks.in_set(SimSet::PreTick),
+                    exit_when_done.in_set(SimSet::PostTick),
+                ),
+            );
+    }
+}
+
+fn setup_headless(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    scenario: Option<Res<Scenario>>,
+    mut ids: ResMut<NextPawnId>,
+) {
+    let map = match &scenario {
+        Some(scenario) => scenario.build_map(&config),
+        None => world::build_world(&config),
+    };
+
+    let center = scenario
+        .and_then(|scenario| scenario.spawn_at)
+        .unwrap_or(config.stockpile());
+    pawn::spawn_pawns(&mut commands, &map, &config, center, &mut ids);
+
+    commands.insert_resource(WorldTrees::from_map(&map));
+    commands.insert_resource(map);
+}
+
+/// Hands the whole run to the sim on the first frame. The real-time clock
+/// stays stopped, so it goes as fast as the ticks can be worked through.
+fn queue_headless_ticks(config: Res<GameConfig>, mut sim: ResMut<Sim>, mut queued: Local<bool>) {
+    if *queued {
+        return;
+    }
+    *queued = true;
+    sim.paused = true;
+    sim.pending_ticks = config.headless_ticks.unwrap_or(0);
+}
+
+fn exit_when_done(sim: Res<Sim>, mut exit: MessageWriter<AppExit>) {
+    if sim.pending_ticks == 0 {
+        info!("headless run finished after {} ticks", sim.ticks);
+        exit.write(AppExit::Success);
+    }
+}
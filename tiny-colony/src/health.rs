@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+
+use crate::colony::Colony;
+use crate::combat::Melee;
+use crate::config::GameConfig;
+use crate::pawn::{Inventory, Pawn, Task};
+
+pub const PAWN_HEALTH: u32 = 100;
+
+/// A starving pawn loses a point of health this often.
+const STARVE_TICKS: u32 = 10;
+/// How much a work accident takes off.
+pub const ACCIDENT_DAMAGE: u32 = 20;
+/// Health a dose of medicine gives back straight away.
+pub const MEDICINE_HEAL: u32 = 30;
+
+const CORPSE_COLOR: Color = Color::srgb(0.45, 0.1, 0.1);
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Health { current: max, max }
+    }
+
+    pub fn is_dead(&self) -> bool {
+        self.current == 0
+    }
+
+    /// Hurt badly enough that the pawn stops to rest when it next can.
+    pub fn needs_rest(&self) -> bool {
+        self.current * 2 < self.max
+    }
+
+    /// Multiplier on how fast the pawn moves and works: half speed at death's
+    /// door, full speed when unhurt.
+    pub fn work_speed(&self) -> f32 {
+        0.5 + 0.5 * self.current as f32 / self.max as f32
+    }
+
+    /// Returns true if this blow was the one that killed.
+    pub fn damage(&mut self, amount: u32) -> bool {
+        let was_alive = !self.is_dead();
+        self.current = self.current.saturating_sub(amount);
+        was_alive && self.is_dead()
+    }
+
+    pub fn heal(&mut self, amount: u32) {
+        if !self.is_dead() {
+            self.current = (self.current + amount).min(self.max);
+        }
+    }
+}
+
+/// Patches a pawn up with a dose from the stockpile, if there's any left.
+/// Returns whether a dose was used.
+pub fn treat(health: &mut Health, colony: &mut Colony) -> bool {
+    if colony.medicine == 0 || health.is_dead() || health.current >= health.max {
+        return false;
+    }
+    colony.medicine -= 1;
+    health.heal(MEDICINE_HEAL);
+    true
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Harm {
+    Combat,
+    Accident,
+    Starvation,
+}
+
+impl Harm {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Harm::Combat => "in combat",
+            Harm::Accident => "in a work accident",
+            Harm::Starvation => "from starvation",
+        }
+    }
+}
+
+/// What's left of a pawn after it dies.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct Corpse {
+    pub pawn: u32,
+    pub x: i32,
+    pub y: i32,
+    pub cause: Harm,
+}
+
+/// Makes every pawn a tick hungrier. Pawns that have gone `hunger_ticks`
+/// without eating start to starve; returns any that starved to death.
+pub fn starve(
+    pawns: &mut Query<(Entity, &mut Pawn, &mut Task, &mut Inventory)>,
+    fighters: &mut Query<(&mut Health, &mut Melee)>,
+    config: &GameConfig,
+) -> Vec<(Entity, u32)> {
+    let mut dead = Vec::new();
+    if config.hunger_ticks == 0 {
+        return dead;
+    }
+
+    for (entity, mut pawn, ..) in pawns.iter_mut() {
+        // the renderer only cares when a pawn moves
+        let pawn = pawn.bypass_change_detection();
+        pawn.hunger += 1;
+        let starving_for = pawn.hunger.saturating_sub(config.hunger_ticks);
+        if pawn.hunger < config.hunger_ticks || !starving_for.is_multiple_of(STARVE_TICKS) {
+            continue;
+        }
+        if let Ok((mut health, _)) = fighters.get_mut(entity)
+            && health.damage(1)
+        {
+            dead.push((entity, pawn.id));
+        }
+    }
+
+    dead.sort_by_key(|&(_, id)| id);
+    dead
+}
+
+pub fn attach_corpse_sprites(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    q: Query<(Entity, &Corpse), Added<Corpse>>,
+) {
+    for (entity, corpse) in &q {
+        let pos = crate::world::grid_to_world(&config, corpse.x, corpse.y);
+        commands.entity(entity).insert((
+            Sprite {
+                color: CORPSE_COLOR,
+                custom_size: Some(Vec2::new(config.tile_size * 0.8, config.tile_size * 0.4)),
+                ..default()
+            },
+            Transform::from_translation(pos.truncate().extend(0.5)),
+        ));
+    }
+}
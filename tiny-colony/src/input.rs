@@ -0,0 +1,234 @@
+use std::collections::BTreeMap;
+
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::config::KEY_BINDINGS_PATH;
+use crate::console::Console;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Action {
+    TogglePause,
+    SpeedNormal,
+    SpeedFast,
+    SpeedFastest,
+    SpeedUltra,
+    SpeedUp,
+    SpeedDown,
+    Step,
+    Select,
+    DesignateMine,
+    PlaceBench,
+    QueueBatch,
+    ClearBill,
+    ToggleConsole,
+    ScrollLogUp,
+    ScrollLogDown,
+    ToggleKeyBindings,
+}
+
+impl Action {
+    pub const ALL: [Action; 17] = [
+        Action::TogglePause,
+        Action::SpeedNormal,
+        Action::SpeedFast,
+        Action::SpeedFastest,
+        Action::SpeedUltra,
+        Action::SpeedUp,
+        Action::SpeedDown,
+        Action::Step,
+        Action::Select,
+        Action::DesignateMine,
+        Action::PlaceBench,
+        Action::QueueBatch,
+        Action::ClearBill,
+        Action::ToggleConsole,
+        Action::ScrollLogUp,
+        Action::ScrollLogDown,
+        Action::ToggleKeyBindings,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+}
+
+impl Binding {
+    pub fn label(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{key:?}"),
+            Binding::Mouse(button) => format!("Mouse {button:?}"),
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct KeyBindings(pub BTreeMap<Action, Binding>);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let bindings = [
+            (Action::TogglePause, Binding::Key(KeyCode::Space)),
+            (Action::SpeedNormal, Binding::Key(KeyCode::Digit1)),
+            (Action::SpeedFast, Binding::Key(KeyCode::Digit2)),
+            (Action::SpeedFastest, Binding::Key(KeyCode::Digit3)),
+            (Action::SpeedUltra, Binding::Key(KeyCode::Digit4)),
+            (Action::SpeedUp, Binding::Key(KeyCode::Equal)),
+            (Action::SpeedDown, Binding::Key(KeyCode::Minus)),
+            (Action::Step, Binding::Key(KeyCode::Period)),
+            (Action::Select, Binding::Mouse(MouseButton::Left)),
+            (Action::DesignateMine, Binding::Key(KeyCode::KeyM)),
+            (Action::PlaceBench, Binding::Key(KeyCode::KeyB)),
+            (Action::QueueBatch, Binding::Key(KeyCode::KeyQ)),
+            (Action::ClearBill, Binding::Key(KeyCode::KeyX)),
+            (Action::ToggleConsole, Binding::Key(KeyCode::Backquote)),
+            (Action::ScrollLogUp, Binding::Key(KeyCode::PageUp)),
+            (Action::ScrollLogDown, Binding::Key(KeyCode::PageDown)),
+            (Action::ToggleKeyBindings, Binding::Key(KeyCode::F1)),
+        ];
+        KeyBindings(bindings.into_iter().collect())
+    }
+}
+
+impl KeyBindings {
+    pub fn get(&self, action: Action) -> Option<Binding> {
+        self.0.get(&action).copied()
+    }
+
+    /// Binds `action` to `binding`. Any other action that was on `binding`
+    /// takes over the one `action` had, so no two actions ever fire together.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let previous = self.0.insert(action, binding);
+        let clashes: Vec<Action> = self
+            .0
+            .iter()
+            .filter(|&(&other, &bound)| other != action && bound == binding)
+            .map(|(&other, _)| other)
+            .collect();
+        for other in clashes {
+            match previous {
+                Some(previous) => self.0.insert(other, previous),
+                None => self.0.remove(&other),
+            };
+        }
+    }
+
+    pub fn load() -> Self {
+        let mut bindings = KeyBindings::default();
+
+        let contents = match std::fs::read_to_string(KEY_BINDINGS_PATH) {
+            Ok(contents) => contents,
+            Err(_) => return bindings,
+        };
+
+        match ron::from_str::<KeyBindings>(&contents) {
+            Ok(loaded) => bindings.0.extend(loaded.0),
+            Err(err) => warn!("ignoring {KEY_BINDINGS_PATH}: {err}"),
+        }
+
+        bindings
+    }
+
+    pub fn save(&self) {
+        let contents = match ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()) {
+            Ok(contents) => contents,
+            Err(err) => {
+                error!("failed to serialize key bindings: {err}");
+                return;
+            }
+        };
+
+        if let Err(err) = std::fs::write(KEY_BINDINGS_PATH, contents) {
+            error!("failed to write {KEY_BINDINGS_PATH}: {err}");
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Actions {
+    just_pressed: HashSet<Action>,
+}
+
+impl Actions {
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed.contains(&action)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Rebinding {
+    pub open: bool,
+    pub waiting: Option<Action>,
+}
+
+pub fn init(commands: &mut Commands) {
+    commands.insert_resource(KeyBindings::load());
+    commands.insert_resource(Actions::default());
+    commands.insert_resource(Rebinding::default());
+}
+
+pub fn update_actions(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    bindings: Res<KeyBindings>,
+    console: Res<Console>,
+    rebinding: Res<Rebinding>,
+    mut actions: ResMut<Actions>,
+) {
+    actions.just_pressed.clear();
+
+    // the console and the rebinding screen own the keyboard while they're up
+    if console.open || rebinding.waiting.is_some() {
+        return;
+    }
+
+    for (&action, &binding) in bindings.0.iter() {
+        let pressed = match binding {
+            Binding::Key(key) => keys.just_pressed(key),
+            Binding::Mouse(button) => buttons.just_pressed(button),
+        };
+        if pressed {
+            actions.just_pressed.insert(action);
+        }
+    }
+}
+
+pub fn capture_rebind(
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    mut bindings: ResMut<KeyBindings>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    let action = match rebinding.waiting {
+        Some(action) => action,
+        None => return,
+    };
+
+    if keys.just_pressed(KeyCode::Escape) {
+        rebinding.waiting = None;
+        return;
+    }
+
+    let binding = match keys.get_just_pressed().next() {
+        Some(&key) => Binding::Key(key),
+        None => match buttons.get_just_pressed().next() {
+            Some(&button) => Binding::Mouse(button),
+            None => return,
+        },
+    };
+
+    bindings.rebind(action, binding);
+    bindings.save();
+    rebinding.waiting = None;
+}
+
+pub fn toggle_rebinding_screen(actions: Res<Actions>, mut rebinding: ResMut<Rebinding>) {
+    if actions.just_pressed(Action::ToggleKeyBindings) {
+        rebinding.open = !rebinding.open;
+        rebinding.waiting = None;
+    }
+}
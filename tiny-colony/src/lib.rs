@@ -0,0 +1,32 @@
+pub mod clock;
+pub mod colony;
+pub mod combat;
+pub mod config;
+pub mod console;
+pub mod crafting;
+pub mod creatures;
+pub mod events;
+pub mod fire;
+pub mod headless;
+pub mod health;
+pub mod input;
+pub mod metrics;
+pub mod mood;
+pub mod pawn;
+pub mod pawn_render;
+pub mod pawn_tasks;
+pub mod population;
+pub mod rng;
+pub mod scenario;
+pub mod sim;
+pub mod tilemap;
+pub mod trade;
+pub mod ui;
+pub mod weather;
+pub mod world;
+
+pub use headless::HeadlessPlugin;
+pub use pawn::{PawnPlugin, PawnSet};
+pub use sim::{SimPlugin, SimSet};
+pub use ui::{UiPlugin, UiSet};
+pub use world::{WorldPlugin, WorldSet};
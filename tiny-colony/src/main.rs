@@ -1,41 +1,50 @@
-mod colony;
-mod config;
-mod pawn;
-mod pawn_tasks;
-mod sim;
-mod ui;
-mod world;
-
+use bevy::log::LogPlugin;
 use bevy::prelude::*;
-use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
+
+use tiny_colony::config::{ConfigError, GameConfig};
+use tiny_colony::scenario::Scenario;
+use tiny_colony::{HeadlessPlugin, PawnPlugin, SimPlugin, UiPlugin, WorldPlugin};
 
 fn main() {
-    App::new()
-        .add_plugins(DefaultPlugins)
-        .add_plugins(FrameTimeDiagnosticsPlugin::default())
-        .add_systems(Startup, setup)
-        .add_systems(
-            Update,
-            (
-                sim::sim_controls,
-                sim::tick_jobs,
-                ui::select_pawn_on_click,
-                ui::update_selected_pawn_visuals,
-                ui::update_wood_ui,
-                ui::update_fps_ui,
-                ui::update_pawn_ui,
-            ),
-        )
-        .run();
+    let (config, scenario) = match load() {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("config error: {err}");
+            std::process::exit(2);
+        }
+    };
+
+    let headless = config.headless_ticks.is_some();
+
+    let mut app = App::new();
+    if headless {
+        app.add_plugins((MinimalPlugins, LogPlugin::default()));
+    } else {
+        app.add_plugins(DefaultPlugins);
+    }
+    app.insert_resource(config);
+    if let Some(scenario) = scenario {
+        app.insert_resource(scenario);
+    }
+    if headless {
+        app.add_plugins((SimPlugin, HeadlessPlugin));
+    } else {
+        app.add_plugins((WorldPlugin, PawnPlugin, SimPlugin, UiPlugin));
+    }
+    app.run();
 }
 
-fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
-    commands.spawn(Camera2d);
+fn load() -> Result<(GameConfig, Option<Scenario>), ConfigError> {
+    let mut config = GameConfig::from_args(std::env::args().skip(1))?;
 
-    ui::spawn_ui(&mut commands);
+    let scenario = match &config.scenario {
+        Some(path) => Some(Scenario::load(path)?),
+        None => None,
+    };
+    if let Some(scenario) = &scenario {
+        scenario.apply(&mut config)?;
+        config.validate()?;
+    }
 
-    let world = world::build_world();
-    pawn::spawn_pawns(&mut commands, &mut images, &world);
-    world::spawn_world_tiles(&mut commands, &world);
-    commands.insert_resource(world);
+    Ok((config, scenario))
 }
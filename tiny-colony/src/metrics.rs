@@ -0,0 +1,186 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::pawn::Task;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Csv,
+    JsonLines,
+}
+
+impl MetricsFormat {
+    /// `.jsonl` and `.json` files get JSON lines, anything else CSV.
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("jsonl" | "json") => MetricsFormat::JsonLines,
+            _ => MetricsFormat::Csv,
+        }
+    }
+}
+
+/// How many pawns are on each [`Task`] variant.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TaskCounts {
+    pub idle: u32,
+    pub go_to_tree: u32,
+    pub chop: u32,
+    pub go_to_rock: u32,
+    pub mine: u32,
+    pub fetch_inputs: u32,
+    pub go_to_bench: u32,
+    pub craft: u32,
+    pub go_to_stockpile: u32,
+    pub drop_off: u32,
+    pub go_to_fire: u32,
+    pub extinguish: u32,
+    pub go_to_pickup: u32,
+    pub rest: u32,
+    pub on_break: u32,
+}
+
+impl TaskCounts {
+    pub fn add(&mut self, task: &Task) {
+        match task {
+            Task::Idle => self.idle += 1,
+            Task::GoToTree(_) => self.go_to_tree += 1,
+            Task::Chop { .. } => self.chop += 1,
+            Task::GoToRock(_) => self.go_to_rock += 1,
+            Task::Mine { .. } => self.mine += 1,
+            Task::FetchInputs(_) => self.fetch_inputs += 1,
+            Task::GoToBench(_) => self.go_to_bench += 1,
+            Task::Craft { .. } => self.craft += 1,
+            Task::GoToStockpile => self.go_to_stockpile += 1,
+            Task::DropOff => self.drop_off += 1,
+            Task::GoToFire(_) => self.go_to_fire += 1,
+            Task::Extinguish { .. } => self.extinguish += 1,
+            Task::GoToPickup(_) => self.go_to_pickup += 1,
+            Task::Rest { .. } => self.rest += 1,
+            Task::Break { .. } => self.on_break += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickMetrics {
+    pub tick: u64,
+    pub wood: u32,
+    pub stone: u32,
+    pub planks: u32,
+    pub blocks: u32,
+    /// Items dropped off since the previous row.
+    pub delivered: u32,
+    pub tasks: TaskCounts,
+    pub reserved: usize,
+    pub trees_left: usize,
+}
+
+const CSV_HEADER: &str =
+    "tick,wood,stone,planks,blocks,delivered,idle,go_to_tree,chop,go_to_rock,mine,fetch_inputs,go_to_bench,craft,go_to_stockpile,drop_off,go_to_fire,extinguish,go_to_pickup,rest,on_break,reserved,trees_left";
+
+/// Appends one row per sampled sim tick to the file given by `--metrics-path`.
+#[derive(Resource)]
+pub struct MetricsWriter {
+    out: BufWriter<File>,
+    format: MetricsFormat,
+    every: u32,
+    delivered: u32,
+}
+
+impl MetricsWriter {
+    pub fn create(path: &str, every: u32) -> io::Result<Self> {
+        let format = MetricsFormat::from_path(path);
+        let mut out = BufWriter::new(File::create(path)?);
+        if format == MetricsFormat::Csv {
+            writeln!(out, "{CSV_HEADER}")?;
+        }
+
+        Ok(MetricsWriter {
+            out,
+            format,
+            every,
+            delivered: 0,
+        })
+    }
+
+    pub fn wants(&self, tick: u64) -> bool {
+        tick.is_multiple_of(self.every as u64)
+    }
+
+    /// Adds a tick's deliveries to the count for the next row.
+    pub fn count_delivered(&mut self, items: u32) {
+        self.delivered = self.delivered.saturating_add(items);
+    }
+
+    /// The deliveries counted since the last call.
+    pub fn take_delivered(&mut self) -> u32 {
+        std::mem::take(&mut self.delivered)
+    }
+
+    pub fn record(&mut self, m: &TickMetrics) -> io::Result<()> {
+        let t = &m.tasks;
+        match self.format {
+            MetricsFormat::Csv => writeln!(
+                self.out,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                m.tick,
+                m.wood,
+                m.stone,
+                m.planks,
+                m.blocks,
+                m.delivered,
+                t.idle,
+                t.go_to_tree,
+                t.chop,
+                t.go_to_rock,
+                t.mine,
+                t.fetch_inputs,
+                t.go_to_bench,
+                t.craft,
+                t.go_to_stockpile,
+                t.drop_off,
+                t.go_to_fire,
+                t.extinguish,
+                t.go_to_pickup,
+                t.rest,
+                t.on_break,
+                m.reserved,
+                m.trees_left,
+            ),
+            MetricsFormat::JsonLines => writeln!(
+                self.out,
+                "{{\"tick\":{},\"wood\":{},\"stone\":{},\"planks\":{},\"blocks\":{},\"delivered\":{},\"tasks\":{{\"idle\":{},\"go_to_tree\":{},\"chop\":{},\"go_to_rock\":{},\"mine\":{},\"fetch_inputs\":{},\"go_to_bench\":{},\"craft\":{},\"go_to_stockpile\":{},\"drop_off\":{},\"go_to_fire\":{},\"extinguish\":{},\"go_to_pickup\":{},\"rest\":{},\"on_break\":{}}},\"reserved\":{},\"trees_left\":{}}}",
+                m.tick,
+                m.wood,
+                m.stone,
+                m.planks,
+                m.blocks,
+                m.delivered,
+                t.idle,
+                t.go_to_tree,
+                t.chop,
+                t.go_to_rock,
+                t.mine,
+                t.fetch_inputs,
+                t.go_to_bench,
+                t.craft,
+                t.go_to_stockpile,
+                t.drop_off,
+                t.go_to_fire,
+                t.extinguish,
+                t.go_to_pickup,
+                t.rest,
+                t.on_break,
+                m.reserved,
+                m.trees_left,
+            ),
+        }
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
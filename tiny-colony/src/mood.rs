@@ -0,0 +1,310 @@
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::health::Health;
+use crate::pawn::{Inventory, Pawn, Task};
+use crate::rng::SimRng;
+use crate::weather::WeatherKind;
+use crate::world::{self, Tile, WorldMap};
+
+/// Where mood sits with nothing good or bad going on.
+const BASE_MOOD: i32 = 50;
+/// Below this a pawn drags its feet.
+pub const LOW_MOOD: i32 = 30;
+/// Below this a pawn drops everything and takes a break.
+pub const BREAK_MOOD: i32 = 15;
+const LOW_MOOD_SPEED: f32 = 0.8;
+
+/// Pawns close enough to chat do so this often.
+const SOCIAL_TICKS: u64 = 10;
+/// Only this many pawns on one tile join in, lowest ids first, so a crowd
+/// standing on the stockpile doesn't turn every chat round quadratic.
+const MAX_CHATTERS_PER_TILE: usize = 4;
+const ARGUMENT_CHANCE: f32 = 0.2;
+/// Opinion at which another pawn counts as a friend.
+pub const FRIEND_OPINION: i32 = 20;
+const MAX_OPINION: i32 = 100;
+
+const NEIGHBOURS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// Something that happened to a pawn and colours its mood for a while.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Thought {
+    SawDeath,
+    LostFriend,
+    ChattedWithFriend,
+    HadArgument,
+    HadBreak,
+}
+
+impl Thought {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Thought::SawDeath => "A colonist died",
+            Thought::LostFriend => "Lost a friend",
+            Thought::ChattedWithFriend => "Chatted with a friend",
+            Thought::HadArgument => "Had an argument",
+            Thought::HadBreak => "Had a break",
+        }
+    }
+
+    pub fn effect(&self) -> i32 {
+        match self {
+            Thought::SawDeath => -15,
+            Thought::LostFriend => -25,
+            Thought::ChattedWithFriend => 5,
+            Thought::HadArgument => -5,
+            Thought::HadBreak => 20,
+        }
+    }
+
+    /// Ticks before the pawn gets over it.
+    pub fn duration(&self) -> u32 {
+        match self {
+            Thought::SawDeath => 1000,
+            Thought::LostFriend => 2000,
+            Thought::ChattedWithFriend => 200,
+            Thought::HadArgument => 200,
+            Thought::HadBreak => 500,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Memory {
+    pub thought: Thought,
+    pub ticks_left: u32,
+}
+
+#[derive(Component, Debug, Clone, PartialEq, Eq)]
+pub struct Mood {
+    pub value: i32,
+    pub memories: Vec<Memory>,
+}
+
+impl Default for Mood {
+    fn default() -> Self {
+        Mood {
+            value: BASE_MOOD,
+            memories: Vec::new(),
+        }
+    }
+}
+
+impl Mood {
+    /// Having the same thought again resets how long it lasts rather than
+    /// stacking.
+    pub fn remember(&mut self, thought: Thought) {
+        self.memories.retain(|m| m.thought != thought);
+        self.memories.push(Memory {
+            thought,
+            ticks_left: thought.duration(),
+        });
+    }
+
+    pub fn work_speed(&self) -> f32 {
+        if self.value < LOW_MOOD {
+            LOW_MOOD_SPEED
+        } else {
+            1.0
+        }
+    }
+
+    pub fn needs_break(&self) -> bool {
+        self.value < BREAK_MOOD
+    }
+}
+
+/// What each pawn thinks of the others it has spent time with, by pawn id.
+/// Ids are never reused, and the dead are forgotten once they've been mourned.
+#[derive(Component, Debug, Clone, Default)]
+pub struct Relationships {
+    pub opinions: HashMap<u32, i32>,
+}
+
+impl Relationships {
+    pub fn opinion_of(&self, pawn: u32) -> i32 {
+        self.opinions.get(&pawn).copied().unwrap_or(0)
+    }
+
+    fn adjust(&mut self, pawn: u32, by: i32) -> i32 {
+        let opinion = self.opinions.entry(pawn).or_default();
+        *opinion = (*opinion + by).clamp(-MAX_OPINION, MAX_OPINION);
+        *opinion
+    }
+
+    /// The strongest feelings first, liked or not, ties by id.
+    pub fn strongest(&self, count: usize) -> Vec<(u32, i32)> {
+        let mut all: Vec<(u32, i32)> = self.opinions.iter().map(|(&id, &o)| (id, o)).collect();
+        all.sort_by_key(|&(id, opinion)| (-opinion.abs(), id));
+        all.truncate(count);
+        all
+    }
+}
+
+/// Everything currently pushing a pawn's mood up or down, with how much.
+pub fn factors(
+    pawn: &Pawn,
+    health: &Health,
+    mood: &Mood,
+    map: &WorldMap,
+    weather: WeatherKind,
+    config: &GameConfig,
+) -> Vec<(&'static str, i32)> {
+    let mut factors = Vec::new();
+    each_factor(pawn, health, mood, map, weather, config, |label, effect| {
+        factors.push((label, effect));
+    });
+    factors
+}
+
+/// Hands each factor to `add` in turn, so the per-tick update can total them
+/// up without building a list for every pawn.
+fn each_factor(
+    pawn: &Pawn,
+    health: &Health,
+    mood: &Mood,
+    map: &WorldMap,
+    weather: WeatherKind,
+    config: &GameConfig,
+    mut add: impl FnMut(&'static str, i32),
+) {
+    if config.hunger_ticks > 0 && pawn.hunger >= config.hunger_ticks {
+        add("Starving", -25);
+    } else if config.hunger_ticks > 0 && pawn.hunger >= config.hunger_ticks / 2 {
+        add("Hungry", -10);
+    }
+
+    if health.needs_rest() {
+        add("In pain", -15);
+    } else if health.current < health.max {
+        add("Hurt", -5);
+    }
+
+    match weather {
+        WeatherKind::Clear => {}
+        WeatherKind::Rain => add("Caught in the rain", -3),
+        WeatherKind::Snow => add("Freezing in the snow", -6),
+    }
+
+    let pos = IVec2::new(pawn.x, pawn.y);
+    let scorched = std::iter::once(pos)
+        .chain(NEIGHBOURS.into_iter().map(|offset| pos + offset))
+        .any(|at| world::in_bounds(map, at.x, at.y) && world::get(map, at.x, at.y) == Tile::Burnt);
+    if scorched {
+        add("Burnt land nearby", -5);
+    }
+
+    for memory in &mood.memories {
+        add(memory.thought.label(), memory.thought.effect());
+    }
+}
+
+/// Lets memories fade by a tick and works out everyone's mood afresh.
+pub fn update(
+    pawns: &Query<(Entity, &mut Pawn, &mut Task, &mut Inventory)>,
+    moods: &mut Query<(&mut Mood, &mut Relationships)>,
+    health: impl Fn(Entity) -> Option<Health>,
+    map: &WorldMap,
+    weather: WeatherKind,
+    config: &GameConfig,
+) {
+    for (entity, pawn, ..) in pawns.iter() {
+        let (Some(health), Ok((mut mood, _))) = (health(entity), moods.get_mut(entity)) else {
+            continue;
+        };
+
+        mood.memories.retain_mut(|m| {
+            m.ticks_left = m.ticks_left.saturating_sub(1);
+            m.ticks_left > 0
+        });
+        let mut total = 0;
+        each_factor(pawn, &health, &mood, map, weather, config, |_, effect| {
+            total += effect;
+        });
+        mood.value = (BASE_MOOD + total).clamp(0, 100);
+    }
+}
+
+/// Pawns standing on the same or neighbouring tiles chat every so often. Most
+/// chats warm them to each other; some turn into arguments.
+pub fn socialize(
+    pawns: &Query<(Entity, &mut Pawn, &mut Task, &mut Inventory)>,
+    moods: &mut Query<(&mut Mood, &mut Relationships)>,
+    is_dead: impl Fn(Entity) -> bool,
+    rng: &mut SimRng,
+    tick: u64,
+) {
+    if !tick.is_multiple_of(SOCIAL_TICKS) {
+        return;
+    }
+
+    let mut by_tile: HashMap<IVec2, Vec<(u32, Entity)>> = HashMap::new();
+    for (entity, pawn, ..) in pawns.iter() {
+        if !is_dead(entity) {
+            by_tile
+                .entry(IVec2::new(pawn.x, pawn.y))
+                .or_default()
+                .push((pawn.id, entity));
+        }
+    }
+    for here in by_tile.values_mut() {
+        if here.len() > MAX_CHATTERS_PER_TILE {
+            here.sort_unstable();
+            here.truncate(MAX_CHATTERS_PER_TILE);
+        }
+    }
+
+    // every pair once, lower id first, in a fixed order so the same seed
+    // makes the same friends
+    let mut pairs = Vec::new();
+    for (&at, here) in &by_tile {
+        for &a in here {
+            for &b in here {
+                if a.0 < b.0 {
+                    pairs.push((a, b));
+                }
+            }
+            for offset in NEIGHBOURS {
+                for &b in by_tile.get(&(at + offset)).into_iter().flatten() {
+                    if a.0 < b.0 {
+                        pairs.push((a, b));
+                    }
+                }
+            }
+        }
+    }
+    pairs.sort_by_key(|&((a, _), (b, _))| (a, b));
+
+    for ((a, ea), (b, eb)) in pairs {
+        let change = if rng.chance(ARGUMENT_CHANCE) {
+            -3
+        } else {
+            1 + rng.below(3) as i32
+        };
+        for (entity, other) in [(ea, b), (eb, a)] {
+            let Ok((mut mood, mut relationships)) = moods.get_mut(entity) else {
+                continue;
+            };
+            let opinion = relationships.adjust(other, change);
+            if change < 0 {
+                mood.remember(Thought::HadArgument);
+            } else if opinion >= FRIEND_OPINION {
+                mood.remember(Thought::ChattedWithFriend);
+            }
+        }
+    }
+}
+
+/// Everyone hears about a death; those who liked the pawn take it harder.
+pub fn mourn(moods: &mut Query<(&mut Mood, &mut Relationships)>, pawn: u32) {
+    for (mut mood, mut relationships) in moods.iter_mut() {
+        let opinion = relationships.opinions.remove(&pawn).unwrap_or(0);
+        if opinion >= FRIEND_OPINION {
+            mood.remember(Thought::LostFriend);
+        } else {
+            mood.remember(Thought::SawDeath);
+        }
+    }
+}
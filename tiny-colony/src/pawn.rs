@@ -3,51 +3,247 @@ use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
 
-use crate::config::*;
-use crate::world::{self, Tile, WorldMap};
+use crate::colony::Item;
+use crate::combat::{Melee, Stance};
+use crate::config::GameConfig;
+use crate::creatures;
+use crate::health::{self, Health, PAWN_HEALTH};
+use crate::mood::{Mood, Relationships};
+use crate::pawn_render;
+use crate::rng::SimRng;
+use crate::scenario::Scenario;
+use crate::trade;
+use crate::world::{self, Path, Tile, WorldMap, WorldSet};
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PawnSet {
+    Spawn,
+}
+
+pub struct PawnPlugin;
+
+impl Plugin for PawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameConfig>()
+            .init_resource::<NextPawnId>()
+            .configure_sets(Startup, PawnSet::Spawn.after(WorldSet::Build))
+            .add_systems(Startup, setup_pawns.in_set(PawnSet::Spawn))
+            .add_systems(
+                PostUpdate,
+                (
+                    pawn_render::update_pawn_batch,
+                    health::attach_corpse_sprites,
+                    (
+                        creatures::attach_creature_sprites,
+                        creatures::update_creature_sprites,
+                    )
+                        .chain(),
+                    (trade::attach_caravan_sprites, trade::update_caravan_sprites).chain(),
+                ),
+            );
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn setup_pawns(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    map: Res<WorldMap>,
+    config: Res<GameConfig>,
+    scenario: Option<Res<Scenario>>,
+    mut ids: ResMut<NextPawnId>,
+) {
+    let circle = images.add(make_circle_image(config.pawn_radius_px));
+    pawn_render::spawn_pawn_batch(&mut commands, &mut meshes, &mut materials, circle);
+
+    let center = scenario
+        .and_then(|scenario| scenario.spawn_at)
+        .unwrap_or(config.stockpile());
+    spawn_pawns(&mut commands, &map, &config, center, &mut ids);
+}
+
+/// Pawn ids are handed out in order and never reused, even once their pawn has
+/// died, so nothing keyed by id mixes a newcomer up with the dead.
+#[derive(Resource, Default)]
+pub struct NextPawnId(pub u32);
+
+impl NextPawnId {
+    pub fn take(&mut self) -> u32 {
+        let id = self.0;
+        self.0 += 1;
+        id
+    }
+}
 
 #[derive(Component)]
 pub struct Pawn {
     pub id: u32,
     pub x: i32,
     pub y: i32,
+    pub idle_ticks: u32,
+    /// Work carried over between ticks when weather slows the pawn down.
+    pub effort: f32,
+    /// Ticks since the pawn last ate.
+    pub hunger: u32,
+    pub path: Path,
 }
 
-#[derive(Component, Debug, Clone, Copy)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Task {
     Idle,
     GoToTree(IVec2),
     Chop { at: IVec2, progress: u8 },
+    GoToRock(IVec2),
+    Mine { at: IVec2, progress: u8 },
+    /// Heading to the stockpile for what the bench at the given tile needs.
+    FetchInputs(IVec2),
+    GoToBench(IVec2),
+    Craft { at: IVec2, progress: u8 },
     GoToStockpile,
     DropOff,
+    GoToFire(IVec2),
+    Extinguish { at: IVec2, progress: u8 },
+    GoToPickup(IVec2),
+    Rest { progress: u8 },
+    Break { progress: u8 },
+}
+
+impl Task {
+    /// The tile this task is working towards, if it needs one reserved.
+    pub fn target_tile(&self) -> Option<IVec2> {
+        match *self {
+            Task::GoToTree(at)
+            | Task::Chop { at, .. }
+            | Task::GoToRock(at)
+            | Task::Mine { at, .. }
+            | Task::FetchInputs(at)
+            | Task::GoToBench(at)
+            | Task::Craft { at, .. }
+            | Task::GoToFire(at)
+            | Task::Extinguish { at, .. }
+            | Task::GoToPickup(at) => Some(at),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Component, Debug, Default, Clone, Copy)]
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct Inventory {
     pub wood: u32,
+    pub stone: u32,
+    pub planks: u32,
+    pub blocks: u32,
+    pub silver: u32,
+    pub medicine: u32,
+    pub steel: u32,
+}
+
+/// Personal knacks that set one pawn apart from the next. The founders are
+/// all average; wanderers who join later roll their own.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Attributes {
+    /// Multiplier on walking speed.
+    pub speed: f32,
+    /// Multiplier on how fast work gets done.
+    pub skill: f32,
+}
+
+impl Default for Attributes {
+    fn default() -> Self {
+        Attributes {
+            speed: 1.0,
+            skill: 1.0,
+        }
+    }
 }
 
-pub fn spawn_pawns(commands: &mut Commands, images: &mut ResMut<Assets<Image>>, map: &WorldMap) {
-    let circle_image = images.add(make_circle_image(PAWN_RADIUS_PX));
+impl Attributes {
+    pub fn roll(rng: &mut SimRng) -> Self {
+        Attributes {
+            speed: 0.8 + 0.4 * rng.next_f32(),
+            skill: 0.8 + 0.4 * rng.next_f32(),
+        }
+    }
+}
+
+impl Inventory {
+    pub fn amount(&self, item: Item) -> u32 {
+        match item {
+            Item::Wood => self.wood,
+            Item::Stone => self.stone,
+            Item::Planks => self.planks,
+            Item::Blocks => self.blocks,
+            Item::Silver => self.silver,
+            Item::Medicine => self.medicine,
+            Item::Steel => self.steel,
+        }
+    }
 
-    let max_radius = ((PAWN_COUNT as f32).sqrt().ceil() as i32) + 5;
-    let stockpile = IVec2 { x: STOCKPILE_X, y: STOCKPILE_Y };
+    pub fn amount_mut(&mut self, item: Item) -> &mut u32 {
+        match item {
+            Item::Wood => &mut self.wood,
+            Item::Stone => &mut self.stone,
+            Item::Planks => &mut self.planks,
+            Item::Blocks => &mut self.blocks,
+            Item::Silver => &mut self.silver,
+            Item::Medicine => &mut self.medicine,
+            Item::Steel => &mut self.steel,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        Item::ALL.iter().all(|&item| self.amount(item) == 0)
+    }
+
+    /// Items of every kind put together.
+    pub fn total(&self) -> u32 {
+        Item::ALL.iter().map(|&item| self.amount(item)).sum()
+    }
 
+    pub fn add(&mut self, other: Inventory) {
+        for item in Item::ALL {
+            *self.amount_mut(item) += other.amount(item);
+        }
+    }
+}
+
+pub fn spawn_pawns(
+    commands: &mut Commands,
+    map: &WorldMap,
+    config: &GameConfig,
+    center: IVec2,
+    ids: &mut NextPawnId,
+) {
     let mut occupied: HashSet<IVec2> = HashSet::new();
+    spawn_pawns_at(commands, map, center, config.pawn_count, ids, &mut occupied);
+}
+
+pub fn spawn_pawns_at(
+    commands: &mut Commands,
+    map: &WorldMap,
+    center: IVec2,
+    count: usize,
+    ids: &mut NextPawnId,
+    occupied: &mut HashSet<IVec2>,
+) -> usize {
+    let max_radius = ((count as f32).sqrt().ceil() as i32) + 5;
     let mut spawned = 0usize;
 
-    for p in spiral_positions(stockpile, max_radius) {
-        if spawned >= PAWN_COUNT {
+    for p in spiral_positions(center, max_radius) {
+        if spawned >= count {
             break;
         }
 
         // bounds check
-        if p.x < 0 || p.x >= MAP_W || p.y < 0 || p.y >= MAP_H {
+        if !world::in_bounds(map, p.x, p.y) {
             continue;
         }
 
         // avoid trees/blocked tiles (customize)
-        if world::get(&map, p.x, p.y) == Tile::Tree {
+        let tile = world::get(map, p.x, p.y);
+        if !tile.is_walkable() || matches!(tile, Tile::Tree | Tile::Burning) {
             continue;
         }
 
@@ -57,30 +253,35 @@ pub fn spawn_pawns(commands: &mut Commands, images: &mut ResMut<Assets<Image>>,
         }
         occupied.insert(p);
 
-        let pos = world::grid_to_world(p.x, p.y);
-        let transform = Transform::from_translation(pos + Vec3::new(0.0, 0.0, 1.0));
-
-        commands.spawn((
-            Pawn {
-                id: spawned as u32,
-                x: p.x,
-                y: p.y,
-            },
-            Sprite {
-                image: circle_image.clone(),
-                color: Color::srgb(0.85, 0.85, 0.95),
-                custom_size: Some(Vec2::splat(TILE_SIZE - 2.0)),
-                ..default()
-            },
-            transform,
-        ))
-        .insert(Task::Idle)
-        .insert(Inventory::default());
-
+        spawn_pawn(commands, ids.take(), p, Attributes::default());
         spawned += 1;
     }
 
-    crate::sim::init(commands);
+    spawned
+}
+
+pub fn spawn_pawn(
+    commands: &mut Commands,
+    id: u32,
+    at: IVec2,
+    attributes: Attributes,
+) -> Entity {
+    commands
+        .spawn(Pawn {
+            id,
+            x: at.x,
+            y: at.y,
+            idle_ticks: 0,
+            effort: 0.0,
+            hunger: 0,
+            path: Path::default(),
+        })
+        .insert(Task::Idle)
+        .insert(Inventory::default())
+        .insert(attributes)
+        .insert((Mood::default(), Relationships::default()))
+        .insert((Health::new(PAWN_HEALTH), Melee::pawn(), Stance::Work))
+        .id()
 }
 
 fn spiral_positions(center: IVec2, max_radius: i32) -> impl Iterator<Item = IVec2> {
@@ -131,7 +332,7 @@ fn make_circle_image(radius: u32) -> Image {
             let inside = dist <= r;
 
             let idx = (y * w + x) * 4;
-            data[idx + 0] = 255;
+            data[idx] = 255;
             data[idx + 1] = 255;
             data[idx + 2] = 255;
             data[idx + 3] = if inside { 255 } else { 0 };
@@ -0,0 +1,164 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::camera::visibility::NoFrustumCulling;
+use bevy::mesh::{Indices, PrimitiveTopology, VertexAttributeValues};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::pawn::Pawn;
+use crate::ui::SelectedPawn;
+use crate::world;
+
+pub const PAWN_COLOR: Color = Color::srgb(0.85, 0.85, 0.95);
+pub const PAWN_COLOR_SELECTED: Color = Color::srgb(1.0, 0.9, 0.4);
+
+/// Every pawn is drawn as one quad of a single shared mesh. The entity and
+/// position of each quad are kept alongside so clicks can be hit-tested
+/// against exactly what is on screen.
+#[derive(Resource)]
+pub struct PawnBatch {
+    pub mesh: Handle<Mesh>,
+    pub entities: Vec<Entity>,
+    pub positions: Vec<Vec2>,
+    /// Which quad each pawn is drawn with.
+    slots: HashMap<Entity, usize>,
+}
+
+pub fn spawn_pawn_batch(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<ColorMaterial>,
+    circle: Handle<Image>,
+) {
+    let mesh = meshes.add(Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    ));
+
+    commands.spawn((
+        Mesh2d(mesh.clone()),
+        MeshMaterial2d(materials.add(ColorMaterial::from(circle))),
+        Transform::from_xyz(0.0, 0.0, 1.0),
+        // the mesh is rebuilt as pawns move, so its spawn-time bounds go stale
+        NoFrustumCulling,
+    ));
+
+    commands.insert_resource(PawnBatch {
+        mesh,
+        entities: Vec::new(),
+        positions: Vec::new(),
+        slots: HashMap::new(),
+    });
+}
+
+pub fn update_pawn_batch(
+    config: Res<GameConfig>,
+    selected: Option<Res<SelectedPawn>>,
+    q_pawns: Query<(Entity, Ref<Pawn>)>,
+    mut removed: RemovedComponents<Pawn>,
+    mut batch: ResMut<PawnBatch>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    let selection_changed = selected.as_ref().is_some_and(|s| s.is_changed());
+    let any_removed = removed.read().count() > 0;
+    let half = (config.tile_size - 2.0) * 0.5;
+    let batch = &mut *batch;
+
+    // with the same pawns in the same order, only the quads of those that
+    // moved need shifting
+    if !selection_changed && !any_removed && q_pawns.iter().len() == batch.entities.len() {
+        let mut moved = Vec::new();
+        for (entity, pawn) in &q_pawns {
+            if !pawn.is_changed() {
+                continue;
+            }
+            let Some(&slot) = batch.slots.get(&entity) else {
+                continue;
+            };
+            let pos = world::grid_to_world(&config, pawn.x, pawn.y).truncate();
+            if batch.positions[slot] != pos {
+                batch.positions[slot] = pos;
+                moved.push(slot);
+            }
+        }
+        if moved.is_empty() {
+            return;
+        }
+        if let Some(mesh) = meshes.get_mut(&batch.mesh)
+            && let Some(VertexAttributeValues::Float32x3(vertices)) =
+                mesh.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            for slot in moved {
+                vertices[slot * 4..slot * 4 + 4]
+                    .copy_from_slice(&corners(batch.positions[slot], half));
+            }
+        }
+        return;
+    }
+
+    let selected = selected.and_then(|s| s.0);
+    batch.entities.clear();
+    batch.positions.clear();
+
+    let mut colors = Vec::with_capacity(q_pawns.iter().len());
+    let mut highlighted = None;
+    for (entity, pawn) in &q_pawns {
+        let pos = world::grid_to_world(&config, pawn.x, pawn.y).truncate();
+        if Some(entity) == selected {
+            highlighted = Some((entity, pos));
+            continue;
+        }
+        batch.entities.push(entity);
+        batch.positions.push(pos);
+        colors.push(PAWN_COLOR.to_linear().to_f32_array());
+    }
+
+    // drawn last so it sits on top of any pawns sharing its tile
+    if let Some((entity, pos)) = highlighted {
+        batch.entities.push(entity);
+        batch.positions.push(pos);
+        colors.push(PAWN_COLOR_SELECTED.to_linear().to_f32_array());
+    }
+
+    batch.slots = batch
+        .entities
+        .iter()
+        .enumerate()
+        .map(|(slot, &e)| (e, slot))
+        .collect();
+
+    let Some(mesh) = meshes.get_mut(&batch.mesh) else {
+        return;
+    };
+    write_quads(mesh, &batch.positions, &colors, half);
+}
+
+fn corners(pos: Vec2, half: f32) -> [[f32; 3]; 4] {
+    [
+        [pos.x - half, pos.y - half, 0.0],
+        [pos.x + half, pos.y - half, 0.0],
+        [pos.x + half, pos.y + half, 0.0],
+        [pos.x - half, pos.y + half, 0.0],
+    ]
+}
+
+fn write_quads(mesh: &mut Mesh, positions: &[Vec2], colors: &[[f32; 4]], half: f32) {
+    let count = positions.len();
+    let mut vertices = Vec::with_capacity(count * 4);
+    let mut uvs = Vec::with_capacity(count * 4);
+    let mut vertex_colors = Vec::with_capacity(count * 4);
+    let mut indices = Vec::with_capacity(count * 6);
+
+    for (i, (pos, color)) in positions.iter().zip(colors).enumerate() {
+        let base = (i * 4) as u32;
+        vertices.extend(corners(*pos, half));
+        uvs.extend([[0.0, 1.0], [1.0, 1.0], [1.0, 0.0], [0.0, 0.0]]);
+        vertex_colors.extend([*color; 4]);
+        indices.extend([base, base + 1, base + 2, base, base + 2, base + 3]);
+    }
+
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, vertex_colors);
+    mesh.insert_indices(Indices::U32(indices));
+}
@@ -1,75 +1,362 @@
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 
-use crate::colony::Colony;
-use crate::config::*;
-use crate::pawn::{Inventory, Pawn, Task};
-use crate::sim::Reservations;
-use crate::world::{self, Tile, WorldMap, WorldTrees};
+use crate::colony::{Colony, Item};
+use crate::config::GameConfig;
+use crate::crafting::Workbenches;
+use crate::events::ColonyEvent;
+use crate::fire::{self, Fires};
+use crate::health::Health;
+use crate::mood::Mood;
+use crate::pawn::{Attributes, Inventory, Pawn, Task};
+use crate::sim::{Reservations, TickState};
+use crate::weather::WeatherKind;
+use crate::world::{self, DroppedItems, MineDesignations, Tile, WorldMap, WorldTrees};
 
+pub const CHOP_TICKS: u8 = 10;
+pub const MINE_TICKS: u8 = 20;
+pub const EXTINGUISH_TICKS: u8 = 5;
+/// A resting pawn gets a point of health back this often.
+const REST_HEAL_TICKS: u8 = 5;
+const BREAK_TICKS: u8 = 30;
+
+/// How far a pawn gets per tick through walking and through work, once weather,
+/// injuries, mood and the pawn's own knack for it are taken into account.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pace {
+    pub moving: f32,
+    pub working: f32,
+}
+
+impl Pace {
+    pub fn new(weather: WeatherKind, health: &Health, attributes: &Attributes, mood: &Mood) -> Self {
+        let fitness = health.work_speed() * mood.work_speed();
+        Pace {
+            moving: weather.move_speed() * fitness * attributes.speed,
+            working: weather.chop_speed() * fitness * attributes.skill,
+        }
+    }
+}
+
+/// Work on a tile that clears it to ground and leaves something to haul back
+/// to the stockpile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Job {
+    Chop,
+    Mine,
+}
+
+impl Job {
+    pub fn tile(self) -> Tile {
+        match self {
+            Job::Chop => Tile::Tree,
+            Job::Mine => Tile::Rock,
+        }
+    }
+
+    pub fn work_ticks(self) -> u8 {
+        match self {
+            Job::Chop => CHOP_TICKS,
+            Job::Mine => MINE_TICKS,
+        }
+    }
+
+    pub fn product(self) -> Inventory {
+        match self {
+            Job::Chop => Inventory {
+                wood: 1,
+                ..default()
+            },
+            Job::Mine => Inventory {
+                stone: 1,
+                ..default()
+            },
+        }
+    }
+
+    fn going(self, at: IVec2) -> Task {
+        match self {
+            Job::Chop => Task::GoToTree(at),
+            Job::Mine => Task::GoToRock(at),
+        }
+    }
+
+    fn working(self, at: IVec2, progress: u8) -> Task {
+        match self {
+            Job::Chop => Task::Chop { at, progress },
+            Job::Mine => Task::Mine { at, progress },
+        }
+    }
+
+    /// Trees are felled standing at the trunk; rock can't be walked on, so
+    /// it's dug from the side.
+    fn in_reach(self, pawn: &Pawn, at: IVec2) -> bool {
+        match self {
+            Job::Chop => pawn.x == at.x && pawn.y == at.y,
+            Job::Mine => is_next_to(pawn, at),
+        }
+    }
+
+    fn can_reach(self, map: &WorldMap, from: IVec2, at: IVec2) -> bool {
+        world::can_reach(map, from, at, self == Job::Mine)
+    }
+}
+
+/// A change to shared sim state requested during the parallel decide phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Intent {
+    Reserve(Job, IVec2),
+    ReserveFire(IVec2),
+    ReservePickup(IVec2),
+    Release(IVec2),
+    Finish(Job, IVec2),
+    Extinguish(IVec2),
+    PickUp(IVec2),
+    ReserveBench(IVec2),
+    /// Takes a batch of the bench's inputs out of the stockpile.
+    TakeInputs(IVec2),
+    Craft(IVec2),
+    Deliver(Inventory),
+    Heal(u32),
+    /// The pawn has laid up hurt and gets a dose of medicine if there is one.
+    Treat,
+    /// A break is over and the pawn feels better for it.
+    Unwind,
+    IdleTooLong(u32),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PawnIntent {
+    pub pawn: u32,
+    pub entity: Entity,
+    pub intent: Intent,
+}
+
+/// Advances one pawn by a tick using only its own components and a read-only
+/// view of the world. Anything that touches shared state comes back as an intent.
+#[allow(clippy::too_many_arguments)]
+pub fn decide(
+    pawn: &mut Pawn,
+    task: &mut Task,
+    inv: &mut Inventory,
+    health: &Health,
+    attributes: &Attributes,
+    mood: &Mood,
+    map: &WorldMap,
+    reservations: &Reservations,
+    world_trees: &WorldTrees,
+    designations: &MineDesignations,
+    fires: &Fires,
+    dropped: &DroppedItems,
+    benches: &Workbenches,
+    colony: &Colony,
+    weather: WeatherKind,
+    config: &GameConfig,
+) -> Option<Intent> {
+    let pace = Pace::new(weather, health, attributes, mood);
+    let (next, intent) = match *task {
+        Task::Idle => handle_idle(
+            pawn,
+            health,
+            mood,
+            map,
+            reservations,
+            world_trees,
+            designations,
+            fires,
+            dropped,
+            benches,
+            colony,
+            config,
+        ),
+        Task::GoToTree(at) => handle_go_to_work(pawn, map, Job::Chop, at, pace),
+        Task::Chop { at, progress } => handle_work(pawn, map, Job::Chop, at, progress, pace),
+        Task::GoToRock(at) => handle_go_to_work(pawn, map, Job::Mine, at, pace),
+        Task::Mine { at, progress } => handle_work(pawn, map, Job::Mine, at, progress, pace),
+        Task::FetchInputs(at) => handle_fetch_inputs(pawn, map, at, benches, config, pace),
+        Task::GoToBench(at) => handle_go_to_bench(pawn, map, at, benches, pace),
+        Task::Craft { at, progress } => handle_craft(pawn, at, progress, benches, pace),
+        Task::GoToStockpile => (handle_go_to_stockpile(pawn, map, config, pace), None),
+        Task::DropOff => handle_drop_off(pawn, inv),
+        Task::GoToFire(at) => handle_go_to_fire(pawn, map, at, fires, pace),
+        Task::Extinguish { at, progress } => handle_extinguish(fires, at, progress),
+        Task::GoToPickup(at) => handle_go_to_pickup(pawn, map, at, dropped, pace),
+        Task::Rest { progress } => handle_rest(health, progress),
+        Task::Break { progress } => handle_break(progress),
+    };
+
+    *task = next;
+    intent
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn handle_idle(
-    pawn_entity: Entity,
-    pawn: &Pawn,
+    pawn: &mut Pawn,
+    health: &Health,
+    mood: &Mood,
     map: &WorldMap,
-    reservations: &mut Reservations,
+    reservations: &Reservations,
     world_trees: &WorldTrees,
-) -> Task {
-    if let Some(tree) =
-        find_nearest_tree(map, IVec2::new(pawn.x, pawn.y), reservations, world_trees)
-    {
-        reservations.reserved_tiles.insert(tree, pawn_entity);
-        Task::GoToTree(tree)
-    } else {
-        Task::Idle
+    designations: &MineDesignations,
+    fires: &Fires,
+    dropped: &DroppedItems,
+    benches: &Workbenches,
+    colony: &Colony,
+    config: &GameConfig,
+) -> (Task, Option<Intent>) {
+    let pos = IVec2::new(pawn.x, pawn.y);
+
+    // a pawn at the end of its rope won't even come out for a fire
+    if mood.needs_break() {
+        return (Task::Break { progress: 0 }, None);
+    }
+
+    // fighting a fire comes before any other work
+    if let Some(at) = find_nearest_fire(pos, reservations, fires) {
+        return (Task::Idle, Some(Intent::ReserveFire(at)));
+    }
+
+    if health.needs_rest() {
+        return (Task::Rest { progress: 0 }, Some(Intent::Treat));
+    }
+
+    // eats at the stockpile well before hunger starts doing damage
+    if config.hunger_ticks > 0 && pawn.hunger >= config.hunger_ticks / 2 {
+        return (Task::GoToStockpile, None);
+    }
+
+    if let Some(at) = find_nearest_pickup(pos, reservations, dropped) {
+        return (Task::Idle, Some(Intent::ReservePickup(at)));
+    }
+
+    if let Some(at) = find_open_bench(pos, reservations, benches, colony) {
+        return (Task::Idle, Some(Intent::ReserveBench(at)));
+    }
+
+    // whichever is closer of a tree to fell and rock to dig, trees on a tie
+    let work = [(Job::Chop, &world_trees.0), (Job::Mine, &designations.0)]
+        .into_iter()
+        .filter_map(|(job, sites)| {
+            find_nearest_work(job, map, pos, reservations, sites).map(|at| (job, at))
+        })
+        .min_by_key(|&(_, at)| (pos - at).abs().element_sum());
+
+    match work {
+        Some((job, at)) => (Task::Idle, Some(Intent::Reserve(job, at))),
+        None => {
+            let intent = note_idle(pawn, config).then_some(Intent::IdleTooLong(pawn.idle_ticks));
+            (Task::Idle, intent)
+        }
     }
 }
 
-pub fn handle_go_to_tree(pawn: &mut Pawn, transform: &mut Transform, at: IVec2) -> Task {
-    let arrived = move_and_update(pawn, transform, at);
-    if arrived {
-        Task::Chop {
-            at: at,
-            progress: 0,
+/// Gives the site up if the way there has been cut off since it was reserved.
+pub fn handle_go_to_work(
+    pawn: &mut Pawn,
+    map: &WorldMap,
+    job: Job,
+    at: IVec2,
+    pace: Pace,
+) -> (Task, Option<Intent>) {
+    if !job.in_reach(pawn, at) {
+        if !job.can_reach(map, IVec2::new(pawn.x, pawn.y), at) {
+            return (Task::Idle, Some(Intent::Release(at)));
         }
+        move_towards(pawn, map, at, pace);
+    }
+    if job.in_reach(pawn, at) {
+        (job.working(at, 0), None)
     } else {
-        Task::GoToTree(at)
+        (job.going(at), None)
     }
 }
 
-pub fn handle_chop(
-    pawn_entity: Entity,
-    map: &mut WorldMap,
-    inv: &mut Inventory,
+pub fn handle_work(
+    pawn: &mut Pawn,
+    map: &WorldMap,
+    job: Job,
     at: IVec2,
     progress: u8,
-    reservations: &mut Reservations,
-    world_trees: &mut WorldTrees,
-    tile_entities: &mut Res<world::TileEntities>,
-    q_tiles: &mut Query<&mut Sprite, With<world::TileSprite>>,
-) -> Task {
-    if world::get(map, at.x, at.y) != Tile::Tree {
-        if reservations.reserved_tiles.get(&at) == Some(&pawn_entity) {
-            reservations.reserved_tiles.remove(&at);
-        }
-        return Task::Idle;
+    pace: Pace,
+) -> (Task, Option<Intent>) {
+    if world::get(map, at.x, at.y) != job.tile() {
+        return (Task::Idle, Some(Intent::Release(at)));
+    }
+    if !spend_effort(pawn, pace.working, 1.0) {
+        return (job.working(at, progress), None);
     }
 
     let next = progress + 1;
-    if next >= 10 {
-        world::set_with_sprite(map, &tile_entities, q_tiles, at.x, at.y, Tile::Ground);
-        inv.wood += 1;
-        world_trees.0.remove(&at);
-        if reservations.reserved_tiles.get(&at) == Some(&pawn_entity) {
-            reservations.reserved_tiles.remove(&at);
-        }
-        Task::GoToStockpile
+    let intent = (next >= job.work_ticks()).then_some(Intent::Finish(job, at));
+    (job.working(at, next), intent)
+}
+
+pub fn handle_fetch_inputs(
+    pawn: &mut Pawn,
+    map: &WorldMap,
+    at: IVec2,
+    benches: &Workbenches,
+    config: &GameConfig,
+    pace: Pace,
+) -> (Task, Option<Intent>) {
+    if !benches.0.contains_key(&at) {
+        return (Task::Idle, Some(Intent::Release(at)));
+    }
+
+    if move_towards(pawn, map, config.stockpile(), pace) {
+        (Task::FetchInputs(at), Some(Intent::TakeInputs(at)))
     } else {
-        Task::Chop { at, progress: next }
+        (Task::FetchInputs(at), None)
     }
 }
 
-pub fn handle_go_to_stockpile(pawn: &mut Pawn, transform: &mut Transform) -> Task {
-    let target = IVec2::new(STOCKPILE_X, STOCKPILE_Y);
-    let arrived = move_and_update(pawn, transform, target);
+/// Carries the inputs over to the bench. If the bench is gone by the time the
+/// pawn gets there, the inputs go back to the stockpile.
+pub fn handle_go_to_bench(
+    pawn: &mut Pawn,
+    map: &WorldMap,
+    at: IVec2,
+    benches: &Workbenches,
+    pace: Pace,
+) -> (Task, Option<Intent>) {
+    if !benches.0.contains_key(&at) {
+        return (Task::GoToStockpile, Some(Intent::Release(at)));
+    }
+
+    if move_towards(pawn, map, at, pace) {
+        (Task::Craft { at, progress: 0 }, None)
+    } else {
+        (Task::GoToBench(at), None)
+    }
+}
+
+pub fn handle_craft(
+    pawn: &mut Pawn,
+    at: IVec2,
+    progress: u8,
+    benches: &Workbenches,
+    pace: Pace,
+) -> (Task, Option<Intent>) {
+    let Some(bench) = benches.0.get(&at) else {
+        return (Task::GoToStockpile, Some(Intent::Release(at)));
+    };
+    if !spend_effort(pawn, pace.working, 1.0) {
+        return (Task::Craft { at, progress }, None);
+    }
+
+    let next = progress + 1;
+    let intent = (next >= bench.kind.recipe().work_ticks).then_some(Intent::Craft(at));
+    (Task::Craft { at, progress: next }, intent)
+}
+
+pub fn handle_go_to_stockpile(
+    pawn: &mut Pawn,
+    map: &WorldMap,
+    config: &GameConfig,
+    pace: Pace,
+) -> Task {
+    let target = config.stockpile();
+    let arrived = move_towards(pawn, map, target, pace);
     if arrived {
         Task::DropOff
     } else {
@@ -77,53 +364,330 @@ pub fn handle_go_to_stockpile(pawn: &mut Pawn, transform: &mut Transform) -> Tas
     }
 }
 
-pub fn handle_drop_off(inv: &mut Inventory, stockpile: &mut Colony) -> Task {
-    if inv.wood > 0 {
-        stockpile.wood += inv.wood;
-        inv.wood = 0;
+pub fn handle_go_to_fire(
+    pawn: &mut Pawn,
+    map: &WorldMap,
+    at: IVec2,
+    fires: &Fires,
+    pace: Pace,
+) -> (Task, Option<Intent>) {
+    if !fires.is_burning(at) {
+        return (Task::Idle, Some(Intent::Release(at)));
+    }
+
+    // beaten out from a neighbouring tile rather than from inside the flames
+    if !is_next_to(pawn, at) {
+        move_towards(pawn, map, at, pace);
+    }
+    if is_next_to(pawn, at) {
+        (Task::Extinguish { at, progress: 0 }, None)
+    } else {
+        (Task::GoToFire(at), None)
     }
-    Task::Idle
 }
 
-fn move_and_update(pawn: &mut Pawn, transform: &mut Transform, target: IVec2) -> bool {
-    step_towards(pawn, target);
-    update_transform(transform, pawn);
-    pawn.x == target.x && pawn.y == target.y
+pub fn handle_extinguish(fires: &Fires, at: IVec2, progress: u8) -> (Task, Option<Intent>) {
+    if !fires.is_burning(at) {
+        return (Task::Idle, Some(Intent::Release(at)));
+    }
+
+    let next = progress + 1;
+    let intent = (next >= EXTINGUISH_TICKS).then_some(Intent::Extinguish(at));
+    (Task::Extinguish { at, progress: next }, intent)
 }
 
-fn update_transform(transform: &mut Transform, pawn: &Pawn) {
-    let pos = world::grid_to_world(pawn.x, pawn.y);
-    transform.translation = pos + Vec3::new(0.0, 0.0, 1.0);
+pub fn handle_go_to_pickup(
+    pawn: &mut Pawn,
+    map: &WorldMap,
+    at: IVec2,
+    dropped: &DroppedItems,
+    pace: Pace,
+) -> (Task, Option<Intent>) {
+    if !dropped.0.contains_key(&at) {
+        return (Task::Idle, Some(Intent::Release(at)));
+    }
+
+    if move_towards(pawn, map, at, pace) {
+        (Task::GoToPickup(at), Some(Intent::PickUp(at)))
+    } else {
+        (Task::GoToPickup(at), None)
+    }
 }
 
-fn step_towards(pawn: &mut Pawn, target: IVec2) {
-    if pawn.x < target.x {
-        pawn.x += 1;
-    } else if pawn.x > target.x {
-        pawn.x -= 1;
-    } else if pawn.y < target.y {
-        pawn.y += 1;
-    } else if pawn.y > target.y {
-        pawn.y -= 1;
+pub fn handle_rest(health: &Health, progress: u8) -> (Task, Option<Intent>) {
+    if health.current >= health.max {
+        return (Task::Idle, None);
+    }
+
+    let next = progress + 1;
+    if next >= REST_HEAL_TICKS {
+        (Task::Rest { progress: 0 }, Some(Intent::Heal(1)))
+    } else {
+        (Task::Rest { progress: next }, None)
     }
 }
 
-fn find_nearest_tree(
+pub fn handle_break(progress: u8) -> (Task, Option<Intent>) {
+    let next = progress + 1;
+    if next >= BREAK_TICKS {
+        (Task::Idle, Some(Intent::Unwind))
+    } else {
+        (Task::Break { progress: next }, None)
+    }
+}
+
+/// Hands over whatever the pawn is carrying, and it eats while it's there.
+pub fn handle_drop_off(pawn: &mut Pawn, inv: &mut Inventory) -> (Task, Option<Intent>) {
+    pawn.hunger = 0;
+    if !inv.is_empty() {
+        let load = std::mem::take(inv);
+        (Task::Idle, Some(Intent::Deliver(load)))
+    } else {
+        (Task::Idle, None)
+    }
+}
+
+/// Applies one intent against shared state. Intents are applied in pawn id
+/// order, so the lowest id wins any conflict over a work site.
+pub fn apply(
+    intent: PawnIntent,
+    pawn: &mut Pawn,
+    task: &mut Task,
+    inv: &mut Inventory,
+    state: &mut TickState,
+    tick: u64,
+) {
+    let entity = intent.entity;
+
+    match intent.intent {
+        Intent::Reserve(job, at) => {
+            // whoever lost the site to a lower id picks again next tick, in
+            // parallel with everyone else, rather than searching here
+            *task = Task::Idle;
+            let from = IVec2::new(pawn.x, pawn.y);
+            if is_free(job, &state.map, &state.reservations, from, at) {
+                pawn.idle_ticks = 0;
+                state.reservations.reserve(at, entity, tick);
+                *task = job.going(at);
+            }
+        }
+        Intent::ReserveFire(at) => {
+            let target = if state.fires.is_burning(at) && !state.reservations.is_reserved(at) {
+                Some(at)
+            } else {
+                find_nearest_fire(
+                    IVec2::new(pawn.x, pawn.y),
+                    &state.reservations,
+                    &state.fires,
+                )
+            };
+
+            // with every fire already covered the pawn goes back to its usual work next tick
+            *task = Task::Idle;
+            if let Some(at) = target {
+                pawn.idle_ticks = 0;
+                state.reservations.reserve(at, entity, tick);
+                *task = Task::GoToFire(at);
+            }
+        }
+        Intent::ReservePickup(at) => {
+            *task = Task::Idle;
+            if state.dropped.0.contains_key(&at) && !state.reservations.is_reserved(at) {
+                pawn.idle_ticks = 0;
+                state.reservations.reserve(at, entity, tick);
+                *task = Task::GoToPickup(at);
+            }
+        }
+        Intent::Release(at) => state.reservations.release(at, entity),
+        Intent::Finish(job, at) => {
+            state.reservations.release(at, entity);
+
+            // someone earlier in the apply order may already have cleared it
+            if world::get(&state.map, at.x, at.y) != job.tile() {
+                *task = Task::Idle;
+                return;
+            }
+
+            world::set(&mut state.map, at.x, at.y, Tile::Ground);
+            inv.add(job.product());
+            match job {
+                Job::Chop => {
+                    state.world_trees.0.remove(&at);
+                    state
+                        .events
+                        .write(ColonyEvent::TreeFelled { pawn: pawn.id, at });
+                }
+                Job::Mine => {
+                    state.designations.0.remove(&at);
+                    state
+                        .events
+                        .write(ColonyEvent::RockMined { pawn: pawn.id, at });
+                }
+            }
+            *task = Task::GoToStockpile;
+        }
+        Intent::Extinguish(at) => {
+            state.reservations.release(at, entity);
+            if fire::extinguish(&mut state.map, &mut state.fires, at) {
+                state
+                    .events
+                    .write(ColonyEvent::FireExtinguished { pawn: pawn.id, at });
+            }
+            *task = Task::Idle;
+        }
+        Intent::PickUp(at) => {
+            state.reservations.release(at, entity);
+            *task = Task::Idle;
+            if let Some(items) = state.dropped.0.remove(&at) {
+                inv.add(items);
+                *task = Task::GoToStockpile;
+            }
+        }
+        Intent::ReserveBench(at) => {
+            *task = Task::Idle;
+            let open = state
+                .benches
+                .0
+                .get(&at)
+                .is_some_and(|bench| bench.wants_work(&state.colony));
+            if open && !state.reservations.is_reserved(at) {
+                pawn.idle_ticks = 0;
+                state.reservations.reserve(at, entity, tick);
+                *task = Task::FetchInputs(at);
+            }
+        }
+        Intent::TakeInputs(at) => {
+            // the stock may have run low since the pawn set out
+            match state.benches.0.get(&at) {
+                Some(bench) if bench.wants_work(&state.colony) => {
+                    let recipe = bench.kind.recipe();
+                    *state.colony.amount_mut(recipe.input) -= recipe.input_amount;
+                    *inv.amount_mut(recipe.input) += recipe.input_amount;
+                    *task = Task::GoToBench(at);
+                }
+                _ => {
+                    state.reservations.release(at, entity);
+                    *task = Task::Idle;
+                }
+            }
+        }
+        Intent::Craft(at) => {
+            state.reservations.release(at, entity);
+            *task = Task::GoToStockpile;
+
+            let Some(bench) = state.benches.0.get_mut(&at) else {
+                return;
+            };
+            let recipe = bench.kind.recipe();
+            let inputs = inv.amount_mut(recipe.input);
+            if *inputs < recipe.input_amount {
+                return;
+            }
+            *inputs -= recipe.input_amount;
+            *inv.amount_mut(recipe.output) += recipe.output_amount;
+            bench.finish_batch();
+            state.events.write(ColonyEvent::ItemsCrafted {
+                pawn: pawn.id,
+                item: recipe.output,
+                amount: recipe.output_amount,
+            });
+        }
+        // these touch the pawn's health and mood, which the caller applies
+        Intent::Heal(_) | Intent::Treat | Intent::Unwind => {}
+        Intent::Deliver(load) => {
+            for item in Item::ALL {
+                let amount = load.amount(item);
+                if amount == 0 {
+                    continue;
+                }
+                *state.colony.amount_mut(item) += amount;
+                let event = match item {
+                    Item::Wood => ColonyEvent::WoodDelivered {
+                        pawn: pawn.id,
+                        amount,
+                    },
+                    _ => ColonyEvent::ItemsDelivered {
+                        pawn: pawn.id,
+                        item,
+                        amount,
+                    },
+                };
+                state.events.write(event);
+            }
+        }
+        Intent::IdleTooLong(ticks) => {
+            state.events.write(ColonyEvent::PawnIdleTooLong {
+                pawn: pawn.id,
+                ticks,
+            });
+        }
+    }
+}
+
+fn note_idle(pawn: &mut Pawn, config: &GameConfig) -> bool {
+    pawn.idle_ticks += 1;
+    pawn.idle_ticks == config.idle_alert_ticks
+}
+
+/// Sites the pawn can't get to, like a tree across deep water or rock walled
+/// in on every side, have to wait until a way opens up.
+fn is_free(
+    job: Job,
+    map: &WorldMap,
+    reservations: &Reservations,
+    from: IVec2,
+    at: IVec2,
+) -> bool {
+    !reservations.is_reserved(at)
+        && world::get(map, at.x, at.y) == job.tile()
+        && job.can_reach(map, from, at)
+}
+
+/// Steps towards `target`, paying the terrain's movement cost for the tile
+/// stepped onto. A pawn with no way through stays where it is.
+fn move_towards(pawn: &mut Pawn, map: &WorldMap, target: IVec2, pace: Pace) -> bool {
+    if let Some(next) = pawn.path.next_step(map, IVec2::new(pawn.x, pawn.y), target)
+        && spend_effort(pawn, pace.moving, world::get(map, next.x, next.y).move_cost())
+    {
+        (pawn.x, pawn.y) = (next.x, next.y);
+    }
+    pawn.x == target.x && pawn.y == target.y
+}
+
+/// Builds up work at `rate` per tick and reports whether `cost` worth is
+/// ready, so slowed pawns act on some ticks and wait on the rest.
+fn spend_effort(pawn: &mut Pawn, rate: f32, cost: f32) -> bool {
+    pawn.effort += rate;
+    if pawn.effort >= cost {
+        pawn.effort -= cost;
+        true
+    } else {
+        false
+    }
+}
+
+fn is_next_to(pawn: &Pawn, at: IVec2) -> bool {
+    (pawn.x - at.x).abs() + (pawn.y - at.y).abs() <= 1
+}
+
+fn find_nearest_work(
+    job: Job,
     map: &WorldMap,
     from: IVec2,
     reservations: &Reservations,
-    world_trees: &WorldTrees,
+    sites: &HashSet<IVec2>,
 ) -> Option<IVec2> {
-    let mut best: Option<(i32, IVec2)> = None;
+    let mut best: Option<((i32, i32, i32), IVec2)> = None;
 
-    for &target in world_trees.0.iter() {
-        let reserved = reservations.reserved_tiles.contains_key(&target);
-        if !reserved && world::get(map, target.x, target.y) == Tile::Tree {
+    for &target in sites.iter() {
+        if is_free(job, map, reservations, from, target) {
             let dist = (from.x - target.x).abs() + (from.y - target.y).abs();
+            // break distance ties by position so the choice doesn't depend on set order
+            let key = (dist, target.y, target.x);
 
             match best {
-                None => best = Some((dist, target)),
-                Some((best_dist, _)) if dist < best_dist => best = Some((dist, target)),
+                None => best = Some((key, target)),
+                Some((best_key, _)) if key < best_key => best = Some((key, target)),
                 _ => {}
             }
         }
@@ -131,3 +695,40 @@ fn find_nearest_tree(
 
     best.map(|(_, pos)| pos)
 }
+
+fn find_nearest_fire(from: IVec2, reservations: &Reservations, fires: &Fires) -> Option<IVec2> {
+    fires
+        .burning
+        .keys()
+        .filter(|&&at| !reservations.is_reserved(at))
+        .min_by_key(|at| ((from - **at).abs().element_sum(), at.y, at.x))
+        .copied()
+}
+
+/// The nearest bench whose bill wants another batch that nobody has taken on yet.
+fn find_open_bench(
+    from: IVec2,
+    reservations: &Reservations,
+    benches: &Workbenches,
+    colony: &Colony,
+) -> Option<IVec2> {
+    benches
+        .0
+        .iter()
+        .filter(|&(&at, bench)| !reservations.is_reserved(at) && bench.wants_work(colony))
+        .map(|(&at, _)| at)
+        .min_by_key(|at| ((from - *at).abs().element_sum(), at.y, at.x))
+}
+
+fn find_nearest_pickup(
+    from: IVec2,
+    reservations: &Reservations,
+    dropped: &DroppedItems,
+) -> Option<IVec2> {
+    dropped
+        .0
+        .keys()
+        .filter(|&&at| !reservations.is_reserved(at))
+        .min_by_key(|at| ((from - **at).abs().element_sum(), at.y, at.x))
+        .copied()
+}
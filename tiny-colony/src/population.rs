@@ -0,0 +1,88 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::colony::Colony;
+use crate::config::GameConfig;
+use crate::creatures;
+use crate::pawn::{self, Attributes, NextPawnId};
+use crate::rng::SimRng;
+use crate::world::{self, Tile, WorldMap};
+
+/// Hours of head counts kept for the population graph.
+pub const HISTORY_HOURS: usize = 48;
+
+/// Wood it takes to knock a bed together.
+pub const BED_WOOD: u32 = 10;
+
+/// Head count sampled once an hour, oldest first.
+#[derive(Resource, Default)]
+pub struct Population {
+    pub history: VecDeque<usize>,
+}
+
+impl Population {
+    pub fn record(&mut self, count: usize) {
+        if self.history.len() >= HISTORY_HOURS {
+            self.history.pop_front();
+        }
+        self.history.push_back(count);
+    }
+
+    pub fn current(&self) -> usize {
+        self.history.back().copied().unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arrival {
+    Joined { pawn: u32, at: IVec2 },
+    TurnedAway,
+}
+
+/// Beds the colony started with plus every one built since.
+pub fn beds(map: &WorldMap, config: &GameConfig) -> usize {
+    config.beds + map.tiles.iter().filter(|&&tile| tile == Tile::Bed).count()
+}
+
+/// Puts a bed down on open ground, paid for from the stockpile.
+pub fn build_bed(map: &mut WorldMap, colony: &mut Colony, at: IVec2) -> Result<(), String> {
+    if !world::in_bounds(map, at.x, at.y) {
+        return Err(format!("({},{}) is outside the map", at.x, at.y));
+    }
+    if world::get(map, at.x, at.y) != Tile::Ground {
+        return Err(format!("({},{}) isn't clear ground", at.x, at.y));
+    }
+    if colony.wood < BED_WOOD {
+        return Err(format!("a bed needs {BED_WOOD} wood"));
+    }
+
+    colony.wood -= BED_WOOD;
+    world::set(map, at.x, at.y, Tile::Bed);
+    Ok(())
+}
+
+/// Runs when a wanderer is due. Word only gets around once the stockpile is
+/// worth `join_wealth` silver, and they only stay if there's a free bed. `count` has to
+/// include anyone who joined earlier this frame and isn't in the world yet.
+pub fn wanderer_arrives(
+    commands: &mut Commands,
+    map: &WorldMap,
+    config: &GameConfig,
+    rng: &mut SimRng,
+    wealth: u32,
+    count: usize,
+    ids: &mut NextPawnId,
+) -> Option<Arrival> {
+    if wealth < config.join_wealth {
+        return None;
+    }
+    if count >= beds(map, config) {
+        return Some(Arrival::TurnedAway);
+    }
+
+    let at = creatures::edge_tile(map, rng)?;
+    let id = ids.take();
+    pawn::spawn_pawn(commands, id, at, Attributes::roll(rng));
+    Some(Arrival::Joined { pawn: id, at })
+}
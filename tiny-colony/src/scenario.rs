@@ -0,0 +1,236 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::config::{ConfigError, GameConfig};
+use crate::pawn::Inventory;
+use crate::world::{self, Tile, WorldMap};
+
+#[derive(Debug, Clone, Deserialize)]
+pub enum MapSource {
+    /// The default forest layout at the given size, with the stockpile in the middle.
+    Generated { width: i32, height: i32 },
+    /// An ASCII map written inline, in the same format as `world::parse_ascii_map`.
+    Ascii(String),
+    /// An ASCII map in a separate file, relative to the scenario file.
+    File(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PawnSpawn {
+    pub count: usize,
+    #[serde(default)]
+    pub at: Option<(i32, i32)>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum Goal {
+    DeliverWood {
+        amount: u32,
+        #[serde(default)]
+        before_tick: Option<u64>,
+    },
+    DeliverStone {
+        amount: u32,
+        #[serde(default)]
+        before_tick: Option<u64>,
+    },
+    ClearForest {
+        #[serde(default)]
+        before_tick: Option<u64>,
+    },
+}
+
+impl Goal {
+    pub fn deadline(&self) -> Option<u64> {
+        match *self {
+            Goal::DeliverWood { before_tick, .. }
+            | Goal::DeliverStone { before_tick, .. }
+            | Goal::ClearForest { before_tick } => before_tick,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        let what = match *self {
+            Goal::DeliverWood { amount, .. } => format!("Deliver {amount} wood"),
+            Goal::DeliverStone { amount, .. } => format!("Deliver {amount} stone"),
+            Goal::ClearForest { .. } => "Clear every tree".to_string(),
+        };
+        match self.deadline() {
+            Some(tick) => format!("{what} before tick {tick}"),
+            None => what,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ScenarioFile {
+    name: String,
+    map: MapSource,
+    pawns: PawnSpawn,
+    #[serde(default)]
+    wood: u32,
+    #[serde(default)]
+    goals: Vec<Goal>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GoalProgress {
+    pub goal: Goal,
+    pub met_at: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won,
+    Lost,
+}
+
+#[derive(Resource)]
+pub struct Scenario {
+    pub name: String,
+    /// `None` for generated maps, which are built from the config once it has
+    /// been adjusted by `apply`.
+    pub map: Option<WorldMap>,
+    pub generated_size: IVec2,
+    pub spawn_at: Option<IVec2>,
+    pub pawn_count: usize,
+    pub wood: u32,
+    pub goals: Vec<GoalProgress>,
+    /// Everything hauled to the stockpile since the scenario started.
+    pub delivered: Inventory,
+    pub outcome: Option<Outcome>,
+}
+
+impl Scenario {
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let contents = read(path)?;
+        let file: ScenarioFile = ron::from_str(&contents).map_err(|err| ConfigError::Parse {
+            path: path.to_string(),
+            err: err.to_string(),
+        })?;
+
+        let (map, generated_size) = match file.map {
+            MapSource::Generated { width, height } => (None, IVec2::new(width, height)),
+            MapSource::Ascii(ascii) => (Some(parse_map(path, &ascii)?), IVec2::ZERO),
+            MapSource::File(map_path) => {
+                let map_path = Path::new(path)
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .join(map_path);
+                let map_path = map_path.to_string_lossy();
+                let ascii = read(&map_path)?;
+                (Some(parse_map(&map_path, &ascii)?), IVec2::ZERO)
+            }
+        };
+
+        Ok(Scenario {
+            name: file.name,
+            map,
+            generated_size,
+            spawn_at: file.pawns.at.map(|(x, y)| IVec2::new(x, y)),
+            pawn_count: file.pawns.count,
+            wood: file.wood,
+            goals: file
+                .goals
+                .into_iter()
+                .map(|goal| GoalProgress { goal, met_at: None })
+                .collect(),
+            delivered: Inventory::default(),
+            outcome: None,
+        })
+    }
+
+    /// Overrides the map size, stockpile and pawn count in `config` with the
+    /// scenario's own.
+    pub fn apply(&self, config: &mut GameConfig) -> Result<(), ConfigError> {
+        match &self.map {
+            Some(map) => {
+                let stockpile = (0..map.height)
+                    .flat_map(|y| (0..map.width).map(move |x| IVec2::new(x, y)))
+                    .find(|p| world::get(map, p.x, p.y) == Tile::Stockpile)
+                    .ok_or_else(|| invalid("map", "has no stockpile tile"))?;
+                config.map_w = map.width;
+                config.map_h = map.height;
+                config.stockpile_x = Some(stockpile.x);
+                config.stockpile_y = Some(stockpile.y);
+            }
+            None => {
+                config.map_w = self.generated_size.x;
+                config.map_h = self.generated_size.y;
+                config.stockpile_x = None;
+                config.stockpile_y = None;
+            }
+        }
+
+        if let Some(at) = self.spawn_at
+            && !((0..config.map_w).contains(&at.x) && (0..config.map_h).contains(&at.y))
+        {
+            return Err(invalid("pawns.at", "must be inside the map"));
+        }
+
+        config.pawn_count = self.pawn_count;
+        Ok(())
+    }
+
+    pub fn build_map(&self, config: &GameConfig) -> WorldMap {
+        match &self.map {
+            Some(map) => map.clone(),
+            None => world::build_world(config),
+        }
+    }
+
+    /// Updates goal progress after a sim tick. Once an outcome is reached it
+    /// sticks, even if the sim is resumed afterwards.
+    pub fn evaluate(&mut self, tick: u64, delivered: Inventory, trees_left: usize) {
+        self.delivered.add(delivered);
+        if self.outcome.is_some() {
+            return;
+        }
+
+        let missed = self.goals.iter().any(|progress| {
+            progress.met_at.is_none() && progress.goal.deadline().is_some_and(|d| tick >= d)
+        });
+        if missed {
+            self.outcome = Some(Outcome::Lost);
+            return;
+        }
+
+        for progress in &mut self.goals {
+            let met = match progress.goal {
+                Goal::DeliverWood { amount, .. } => self.delivered.wood >= amount,
+                Goal::DeliverStone { amount, .. } => self.delivered.stone >= amount,
+                Goal::ClearForest { .. } => trees_left == 0,
+            };
+            if met && progress.met_at.is_none() {
+                progress.met_at = Some(tick);
+            }
+        }
+
+        if !self.goals.is_empty() && self.goals.iter().all(|progress| progress.met_at.is_some()) {
+            self.outcome = Some(Outcome::Won);
+        }
+    }
+}
+
+fn read(path: &str) -> Result<String, ConfigError> {
+    std::fs::read_to_string(path).map_err(|err| ConfigError::Io {
+        path: path.to_string(),
+        err,
+    })
+}
+
+fn parse_map(path: &str, ascii: &str) -> Result<WorldMap, ConfigError> {
+    world::parse_ascii_map(ascii).map_err(|err| ConfigError::Parse {
+        path: path.to_string(),
+        err,
+    })
+}
+
+fn invalid(field: &'static str, reason: &str) -> ConfigError {
+    ConfigError::Invalid {
+        field,
+        reason: reason.to_string(),
+    }
+}
@@ -1,96 +1,728 @@
+use bevy::ecs::system::SystemParam;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
+use bevy::utils::Parallel;
 
+use crate::clock::{ClockTime, HourChanged};
 use crate::colony::Colony;
-use crate::pawn::{Inventory, Pawn, Task};
-use crate::pawn_tasks;
-use crate::world::{self, WorldMap};
+use crate::combat::{self, Casualty, Combatants};
+use crate::config::GameConfig;
+use crate::crafting::Workbenches;
+use crate::creatures::{self, Wildlife};
+use crate::events::{self, ColonyEvent, EventLog};
+use crate::fire::{self, Fires};
+use crate::health::{self, ACCIDENT_DAMAGE, Corpse, Harm};
+use crate::input::{Action, Actions};
+use crate::metrics::{MetricsWriter, TaskCounts, TickMetrics};
+use crate::mood::{self, Mood, Relationships, Thought};
+use crate::pawn::{Attributes, Inventory, NextPawnId, Pawn, Task};
+use crate::pawn_tasks::{self, Intent, PawnIntent};
+use crate::population::{self, Arrival, Population};
+use crate::rng::SimRng;
+use crate::scenario::Scenario;
+use crate::trade::{self, Caravan, CaravanNews};
+use crate::weather::{self, Season, Weather};
+use crate::world::{self, DroppedItems, MineDesignations, WorldMap, WorldTrees};
+
+const SPEED_STEPS: [f32; 4] = [1.0, 2.0, 4.0, 16.0];
+/// The fastest the sim can be set to run, from the console or otherwise.
+pub const MAX_SPEED: f32 = SPEED_STEPS[SPEED_STEPS.len() - 1];
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimSet {
+    PreTick,
+    Tick,
+    PostTick,
+}
+
+pub struct SimPlugin;
+
+impl Plugin for SimPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameConfig>()
+            .init_resource::<NextPawnId>()
+            .add_message::<ColonyEvent>()
+            .add_message::<HourChanged>()
+            .configure_sets(
+                Update,
+                (SimSet::PreTick, SimSet::Tick, SimSet::PostTick).chain(),
+            )
+            .add_observer(release_on_task_replace)
+            .add_systems(Startup, setup_sim)
+            .add_systems(
+                Update,
+                (
+                    release_abandoned_reservations.in_set(SimSet::PreTick),
+                    tick_jobs.in_set(SimSet::Tick),
+                    events::record_events.in_set(SimSet::PostTick),
+                ),
+            );
+
+        #[cfg(debug_assertions)]
+        app.add_systems(Update, check_reservations.in_set(SimSet::PostTick));
+    }
+}
+
+fn setup_sim(mut commands: Commands, config: Res<GameConfig>, scenario: Option<Res<Scenario>>) {
+    init(&mut commands, &config);
+    if let Some(scenario) = scenario {
+        commands.insert_resource(Colony(Inventory {
+            wood: scenario.wood,
+            ..default()
+        }));
+    }
+}
 
 #[derive(Resource)]
 pub struct Sim {
     pub paused: bool,
     pub speed: f32,
     pub tick: Timer,
+    pub ticks: u64,
+    pub pending_ticks: u32,
 }
 
-#[derive(Resource)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reservation {
+    pub at: IVec2,
+    pub since: u64,
+}
+
+/// Tiles claimed by pawns, indexed both ways. A pawn holds at most one
+/// reservation at a time.
+#[derive(Resource, Default)]
 pub struct Reservations {
-    pub reserved_tiles: HashMap<IVec2, Entity>
+    pub reserved_tiles: HashMap<IVec2, Entity>,
+    pub by_pawn: HashMap<Entity, Reservation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReservationLeak {
+    pub at: IVec2,
+    pub pawn: Entity,
+    pub reason: &'static str,
+}
+
+impl Reservations {
+    pub fn reserve(&mut self, at: IVec2, pawn: Entity, tick: u64) {
+        self.release_pawn(pawn);
+        self.reserved_tiles.insert(at, pawn);
+        self.by_pawn.insert(pawn, Reservation { at, since: tick });
+    }
+
+    pub fn release(&mut self, at: IVec2, pawn: Entity) {
+        if self.reserved_tiles.get(&at) == Some(&pawn) {
+            self.reserved_tiles.remove(&at);
+            self.by_pawn.remove(&pawn);
+        }
+    }
+
+    pub fn release_pawn(&mut self, pawn: Entity) -> Option<IVec2> {
+        let reservation = self.by_pawn.remove(&pawn)?;
+        self.reserved_tiles.remove(&reservation.at);
+        Some(reservation.at)
+    }
+
+    pub fn is_reserved(&self, at: IVec2) -> bool {
+        self.reserved_tiles.contains_key(&at)
+    }
+
+    /// Pawns that have held their reservation for more than `timeout` ticks.
+    pub fn expired(&self, tick: u64, timeout: u32) -> Vec<Entity> {
+        self.by_pawn
+            .iter()
+            .filter(|(_, r)| tick.saturating_sub(r.since) > timeout as u64)
+            .map(|(&pawn, _)| pawn)
+            .collect()
+    }
+
+    /// Reservations that nothing will ever release: the holder is gone, isn't
+    /// working on the tile, or the two indexes disagree.
+    pub fn leaks(&self, task_of: impl Fn(Entity) -> Option<Task>) -> Vec<ReservationLeak> {
+        let mut leaks = Vec::new();
+
+        for (&at, &pawn) in &self.reserved_tiles {
+            let reason = if self.by_pawn.get(&pawn).map(|r| r.at) != Some(at) {
+                "missing from pawn index"
+            } else {
+                match task_of(pawn) {
+                    None => "holder no longer exists",
+                    Some(task) if task.target_tile() != Some(at) => {
+                        "holder is doing something else"
+                    }
+                    Some(_) => continue,
+                }
+            };
+            leaks.push(ReservationLeak { at, pawn, reason });
+        }
+
+        for (&pawn, r) in &self.by_pawn {
+            if self.reserved_tiles.get(&r.at) != Some(&pawn) {
+                leaks.push(ReservationLeak {
+                    at: r.at,
+                    pawn,
+                    reason: "missing from tile index",
+                });
+            }
+        }
+
+        leaks
+    }
 }
 
-pub fn init(commands: &mut Commands) {
+pub fn init(commands: &mut Commands, config: &GameConfig) {
     commands.insert_resource(Sim {
         paused: false,
         speed: 1.0,
-        tick: Timer::from_seconds(0.10, TimerMode::Repeating), // 10 Hz
+        tick: Timer::from_seconds(config.tick_seconds(), TimerMode::Repeating),
+        ticks: 0,
+        pending_ticks: 0,
     });
 
     commands.insert_resource(Colony::default());
-    commands.insert_resource(Reservations {
-        reserved_tiles: HashMap::new(),
-    });
+    commands.insert_resource(EventLog::default());
+    commands.insert_resource(Reservations::default());
+    commands.insert_resource(Fires::default());
+    commands.insert_resource(DroppedItems::default());
+    commands.insert_resource(MineDesignations::default());
+    commands.insert_resource(Workbenches::default());
+    commands.insert_resource(Wildlife::default());
+    commands.insert_resource(Population::default());
+    commands.insert_resource(SimRng::new(config.seed));
+    commands.insert_resource(Weather::default());
+
+    if let Some(path) = &config.metrics_path {
+        match MetricsWriter::create(path, config.metrics_every) {
+            Ok(writer) => commands.insert_resource(writer),
+            Err(err) => warn!("failed to open metrics file {path}: {err}"),
+        }
+    }
 }
 
-pub fn sim_controls(keys: Res<ButtonInput<KeyCode>>, mut sim: ResMut<Sim>) {
-    if keys.just_pressed(KeyCode::Space) {
+pub fn sim_controls(actions: Res<Actions>, mut sim: ResMut<Sim>) {
+    if actions.just_pressed(Action::TogglePause) {
         sim.paused = !sim.paused;
     }
 
-    if keys.just_pressed(KeyCode::Digit1) {
-        sim.speed = 1.0;
-    } else if keys.just_pressed(KeyCode::Digit2) {
-        sim.speed = 2.0;
-    } else if keys.just_pressed(KeyCode::Digit3) {
-        sim.speed = 4.0;
+    if actions.just_pressed(Action::Step) {
+        sim.paused = true;
+        sim.pending_ticks += 1;
+    }
+
+    if actions.just_pressed(Action::SpeedNormal) {
+        sim.speed = SPEED_STEPS[0];
+    } else if actions.just_pressed(Action::SpeedFast) {
+        sim.speed = SPEED_STEPS[1];
+    } else if actions.just_pressed(Action::SpeedFastest) {
+        sim.speed = SPEED_STEPS[2];
+    } else if actions.just_pressed(Action::SpeedUltra) {
+        sim.speed = SPEED_STEPS[3];
+    } else if actions.just_pressed(Action::SpeedUp) {
+        sim.speed = SPEED_STEPS
+            .into_iter()
+            .find(|&step| step > sim.speed)
+            .unwrap_or(sim.speed);
+    } else if actions.just_pressed(Action::SpeedDown) {
+        sim.speed = SPEED_STEPS
+            .into_iter()
+            .rev()
+            .find(|&step| step < sim.speed)
+            .unwrap_or(sim.speed);
     }
 }
 
+#[derive(SystemParam)]
+pub struct TickState<'w> {
+    pub map: ResMut<'w, WorldMap>,
+    pub reservations: ResMut<'w, Reservations>,
+    pub world_trees: ResMut<'w, WorldTrees>,
+    pub designations: ResMut<'w, MineDesignations>,
+    pub fires: ResMut<'w, Fires>,
+    pub dropped: ResMut<'w, DroppedItems>,
+    pub benches: ResMut<'w, Workbenches>,
+    pub colony: ResMut<'w, Colony>,
+    pub population: ResMut<'w, Population>,
+    pub pawn_ids: ResMut<'w, NextPawnId>,
+    pub weather: ResMut<'w, Weather>,
+    pub rng: ResMut<'w, SimRng>,
+    pub scenario: Option<ResMut<'w, Scenario>>,
+    pub events: MessageWriter<'w, ColonyEvent>,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn tick_jobs(
+    mut commands: Commands,
     time: Res<Time>,
+    config: Res<GameConfig>,
     mut sim: ResMut<Sim>,
-    mut map: ResMut<WorldMap>,
-    mut stockpile: ResMut<Colony>,
-    mut q: Query<(Entity, &mut Pawn, &mut Transform, &mut Task, &mut Inventory)>,
-    mut tile_entities: Res<world::TileEntities>,
-    mut q_tiles: Query<&mut Sprite, With<world::TileSprite>>,
+    mut q: Query<(Entity, &mut Pawn, &mut Task, &mut Inventory)>,
+    mut state: TickState,
+    mut intents: Local<Parallel<Vec<PawnIntent>>>,
+    mut metrics: Option<ResMut<MetricsWriter>>,
+    mut hours: MessageWriter<HourChanged>,
+    mut combat: Combatants,
+    attributes: Query<&Attributes>,
+    mut moods: Query<(&mut Mood, &mut Relationships)>,
+    mut caravans: Query<(Entity, &mut Caravan)>,
+    mut trees_before: Local<usize>,
+) {
+    let mut ticks = 0;
+    if !sim.paused {
+        let speed = sim.speed;
+        sim.tick.tick(time.delta().mul_f32(speed));
+        // run every tick that came due this frame, but drop the excess after a
+        // long stall instead of trying to catch up on all of it
+        ticks = sim
+            .tick
+            .times_finished_this_tick()
+            .min(config.max_ticks_per_frame);
+    }
+    // ticks queued from the console fill whatever is left of this frame's
+    // allowance and the rest wait for the frames after
+    let queued = sim.pending_ticks.min(config.max_ticks_per_frame - ticks);
+    sim.pending_ticks -= queued;
+    ticks += queued;
+
+    // only one caravan on the road at a time; one spawned this frame won't
+    // show up in the query until the commands are applied
+    let mut caravan_welcome = caravans.is_empty();
+    // likewise newcomers, who still need a bed if another hour passes
+    let mut arrived = 0;
+
+    for _ in 0..ticks {
+        sim.ticks += 1;
+        // reachability is looked up by arrivals and all through decide, so
+        // catch up on any tiles opened or closed off since the last tick
+        world::refresh_regions(state.map.bypass_change_detection());
+
+        if sim.ticks.is_multiple_of(config.ticks_per_hour as u64) {
+            let time = ClockTime::at(sim.ticks, config.ticks_per_hour);
+            hours.write(HourChanged {
+                day: time.day,
+                hour: time.hour,
+            });
+
+            let season = Season::of_day(time.day, config.days_per_season);
+            if config.weather_hours > 0 && time.hour.is_multiple_of(config.weather_hours) {
+                let state = &mut state;
+                state.weather.roll(season, &mut state.rng);
+            } else if state.weather.season != season {
+                state.weather.season = season;
+            }
+
+            let state = &mut state;
+            if let Some((size, at)) = creatures::spawn_arrivals(
+                &mut commands,
+                &mut combat.wildlife,
+                &state.map,
+                &config,
+                &mut state.rng,
+                sim.ticks / config.ticks_per_hour as u64,
+            ) {
+                state.events.write(ColonyEvent::RaidArrived { size, at });
+            }
+
+            let count = q.iter().filter(|(e, ..)| !combat.is_dead(*e)).count() + arrived;
+            let hour = sim.ticks / config.ticks_per_hour as u64;
+            let mut joined = 0;
+            if config.wanderer_every_hours > 0
+                && hour.is_multiple_of(config.wanderer_every_hours as u64)
+            {
+                match population::wanderer_arrives(
+                    &mut commands,
+                    &state.map,
+                    &config,
+                    &mut state.rng,
+                    trade::wealth(&state.colony),
+                    count,
+                    &mut state.pawn_ids,
+                ) {
+                    Some(Arrival::Joined { pawn, at }) => {
+                        joined = 1;
+                        arrived += 1;
+                        state.events.write(ColonyEvent::PawnJoined { pawn, at });
+                    }
+                    Some(Arrival::TurnedAway) => {
+                        state.events.write(ColonyEvent::WandererTurnedAway);
+                    }
+                    None => {}
+                }
+            }
+            state.population.record(count + joined);
+
+            if config.caravan_every_hours > 0
+                && hour.is_multiple_of(config.caravan_every_hours as u64)
+                && caravan_welcome
+                && let Some(at) =
+                    trade::caravan_arrives(&mut commands, &state.map, &config, &mut state.rng)
+            {
+                caravan_welcome = false;
+                state.events.write(ColonyEvent::CaravanArrived { at });
+            }
+        }
+
+        for (entity, _) in health::starve(&mut q, &mut combat.fighters, &config) {
+            bury(&mut commands, &mut state, &mut q, &mut moods, entity, Harm::Starvation);
+        }
+
+        // fight or flight comes first; anyone caught up in it skips their work this tick
+        let casualties = combat::fight(&mut q, &mut combat, &state.map, &config, &mut state.rng);
+        for casualty in casualties {
+            match casualty {
+                Casualty::Pawn { entity, .. } => {
+                    bury(&mut commands, &mut state, &mut q, &mut moods, entity, Harm::Combat);
+                }
+                Casualty::Creature { entity, kind, at } => {
+                    commands.entity(entity).despawn();
+                    state.events.write(ColonyEvent::CreatureKilled { kind, at });
+                }
+            }
+        }
+
+        // decide: every pawn in parallel against a read-only view of the world
+        {
+            let map = &*state.map;
+            let reservations = &*state.reservations;
+            let world_trees = &*state.world_trees;
+            let designations = &*state.designations;
+            let fires = &*state.fires;
+            let dropped = &*state.dropped;
+            let benches = &*state.benches;
+            let colony = &*state.colony;
+            let combat = &combat;
+            let moods = &moods;
+            let weather = state.weather.kind;
+            let config = &*config;
+            let intents = &*intents;
+
+            q.par_iter_mut()
+                .for_each(|(entity, mut pawn, mut task, mut inv)| {
+                    if combat.is_engaged(entity) {
+                        return;
+                    }
+                    let (Ok((health, _)), Ok(attributes), Ok((mood, _))) = (
+                        combat.fighters.get(entity),
+                        attributes.get(entity),
+                        moods.get(entity),
+                    ) else {
+                        return;
+                    };
+                    // most ticks only touch counters and the cached path, so the
+                    // pawn is only flagged as changed when it actually moves
+                    let from = (pawn.x, pawn.y);
+                    let intent = pawn_tasks::decide(
+                        pawn.bypass_change_detection(),
+                        &mut task,
+                        &mut inv,
+                        health,
+                        attributes,
+                        mood,
+                        map,
+                        reservations,
+                        world_trees,
+                        designations,
+                        fires,
+                        dropped,
+                        benches,
+                        colony,
+                        weather,
+                        config,
+                    );
+                    if (pawn.x, pawn.y) != from {
+                        pawn.set_changed();
+                    }
+                    if let Some(intent) = intent {
+                        intents.borrow_local_mut().push(PawnIntent {
+                            pawn: pawn.id,
+                            entity,
+                            intent,
+                        });
+                    }
+                });
+        }
+
+        // apply: one at a time in pawn id order so conflicts resolve the same way every run
+        let mut pending: Vec<PawnIntent> = intents.drain().collect();
+        pending.sort_by_key(|intent| (intent.pawn, intent.entity));
+
+        let mut delivered = Inventory::default();
+        for intent in pending {
+            if let Intent::Deliver(load) = intent.intent {
+                delivered.add(load);
+            }
+            let entity = intent.entity;
+            let finished = match q.get_mut(entity) {
+                Ok((_, mut pawn, mut task, mut inv)) => {
+                    let tick = sim.ticks;
+                    pawn_tasks::apply(intent, &mut pawn, &mut task, &mut inv, &mut state, tick);
+                    matches!(intent.intent, Intent::Finish(..)) && *task == Task::GoToStockpile
+                }
+                Err(_) => false,
+            };
+
+            if intent.intent == Intent::Unwind
+                && let Ok((mut mood, _)) = moods.get_mut(entity)
+            {
+                mood.remember(Thought::HadBreak);
+            }
+
+            let Ok((mut health, _)) = combat.fighters.get_mut(entity) else {
+                continue;
+            };
+            if let Intent::Heal(amount) = intent.intent {
+                health.heal(amount);
+            }
+            if intent.intent == Intent::Treat && health::treat(&mut health, &mut state.colony) {
+                state.events.write(ColonyEvent::PawnTreated { pawn: intent.pawn });
+            }
+            if finished && config.accident_chance > 0.0 && state.rng.chance(config.accident_chance) {
+                let died = health.damage(ACCIDENT_DAMAGE);
+                state.events.write(ColonyEvent::PawnInjured {
+                    pawn: intent.pawn,
+                    damage: ACCIDENT_DAMAGE,
+                });
+                if died {
+                    bury(&mut commands, &mut state, &mut q, &mut moods, entity, Harm::Accident);
+                }
+            }
+        }
+
+        // anything held this long is stuck; free the tile and let the pawn start over
+        if config.reservation_timeout_ticks > 0 {
+            for pawn in state
+                .reservations
+                .expired(sim.ticks, config.reservation_timeout_ticks)
+            {
+                state.reservations.release_pawn(pawn);
+                if let Ok((_, _, mut task, _)) = q.get_mut(pawn) {
+                    *task = Task::Idle;
+                }
+            }
+        }
+
+        burn(&mut state, &config, &mut q);
+
+        for (entity, mut caravan) in &mut caravans {
+            match trade::advance(&mut caravan, &state.map, &config) {
+                Some(CaravanNews::OpenForTrade) => {
+                    state.events.write(ColonyEvent::CaravanOpenForTrade);
+                }
+                Some(CaravanNews::Left) => {
+                    commands.entity(entity).despawn();
+                    caravan_welcome = true;
+                    state.events.write(ColonyEvent::CaravanLeft);
+                }
+                None => {}
+            }
+        }
+
+        let growth = config.tree_growth_chance * state.weather.growth();
+        if growth > 0.0 {
+            let state = &mut state;
+            weather::grow_trees(
+                &mut state.map,
+                &mut state.world_trees,
+                &mut state.rng,
+                growth,
+            );
+        }
+
+        // however the last tree went: felled, burnt or cleared from the console
+        let trees = state.world_trees.0.len();
+        if trees == 0 && *trees_before > 0 {
+            state.events.write(ColonyEvent::NoTreesLeft);
+        }
+        *trees_before = trees;
+
+        mood::socialize(
+            &q,
+            &mut moods,
+            |entity| combat.is_dead(entity),
+            &mut state.rng,
+            sim.ticks,
+        );
+        mood::update(
+            &q,
+            &mut moods,
+            |entity| combat.fighters.get(entity).ok().map(|(health, _)| *health),
+            &state.map,
+            state.weather.kind,
+            &config,
+        );
+
+        if let Some(writer) = metrics.as_deref_mut() {
+            writer.count_delivered(delivered.total());
+        }
+        if let Some(writer) = metrics.as_deref_mut()
+            && writer.wants(sim.ticks)
+        {
+            let mut tasks = TaskCounts::default();
+            for (_, _, task, _) in &q {
+                tasks.add(task);
+            }
+            let row = TickMetrics {
+                tick: sim.ticks,
+                wood: state.colony.wood,
+                stone: state.colony.stone,
+                planks: state.colony.planks,
+                blocks: state.colony.blocks,
+                delivered: writer.take_delivered(),
+                tasks,
+                reserved: state.reservations.reserved_tiles.len(),
+                trees_left: state.world_trees.0.len(),
+            };
+            if let Err(err) = writer.record(&row) {
+                warn!("failed to write metrics, disabling: {err}");
+                commands.remove_resource::<MetricsWriter>();
+                metrics = None;
+            }
+        }
+
+        if let Some(scenario) = state.scenario.as_deref_mut() {
+            let was_running = scenario.outcome.is_none();
+            scenario.evaluate(sim.ticks, delivered, state.world_trees.0.len());
+            // stop on the deciding tick so the result screen shows where it ended
+            if was_running && scenario.outcome.is_some() {
+                sim.paused = true;
+                sim.pending_ticks = 0;
+                break;
+            }
+        }
+    }
+
+    if let Some(writer) = metrics.as_deref_mut()
+        && ticks > 0
+        && let Err(err) = writer.flush()
+    {
+        warn!("failed to flush metrics: {err}");
+    }
+}
+
+/// Starts and spreads fires for one tick. Anyone working a tile that caught
+/// fire or burnt out loses their claim on it and starts over.
+fn burn(
+    state: &mut TickState,
+    config: &GameConfig,
+    q: &mut Query<(Entity, &mut Pawn, &mut Task, &mut Inventory)>,
+) {
+    let state = &mut *state;
+
+    if state.weather.is_dry()
+        && let Some(at) = fire::random_ignition(
+            &mut state.map,
+            &mut state.world_trees,
+            &mut state.fires,
+            &mut state.rng,
+            config.fire_chance,
+        )
+    {
+        state.events.write(ColonyEvent::FireStarted { at });
+        drop_claim(&mut state.reservations, q, at);
+    }
+
+    let update = fire::spread(
+        &mut state.map,
+        &mut state.world_trees,
+        &mut state.fires,
+        &mut state.rng,
+        config.fire_spread_chance * state.weather.kind.fire_spread(),
+    );
+    for at in update.ignited.into_iter().chain(update.burnt_out) {
+        drop_claim(&mut state.reservations, q, at);
+    }
+}
+
+/// Clears away a pawn that just died: its claim is freed, whatever it was
+/// carrying is left on the ground for someone else to haul, a corpse marks
+/// the spot and the rest of the colony mourns.
+fn bury(
+    commands: &mut Commands,
+    state: &mut TickState,
+    q: &mut Query<(Entity, &mut Pawn, &mut Task, &mut Inventory)>,
+    moods: &mut Query<(&mut Mood, &mut Relationships)>,
+    entity: Entity,
+    cause: Harm,
+) {
+    let Ok((_, pawn, _, mut inv)) = q.get_mut(entity) else {
+        return;
+    };
+    let at = IVec2::new(pawn.x, pawn.y);
+
+    state.reservations.release_pawn(entity);
+    if !inv.is_empty() {
+        state.dropped.0.entry(at).or_default().add(*inv);
+        *inv = Inventory::default();
+    }
+
+    commands.spawn(Corpse {
+        pawn: pawn.id,
+        x: at.x,
+        y: at.y,
+        cause,
+    });
+    commands.entity(entity).despawn();
+    mood::mourn(moods, pawn.id);
+    state.events.write(ColonyEvent::PawnDied {
+        pawn: pawn.id,
+        cause,
+    });
+}
+
+fn drop_claim(
+    reservations: &mut Reservations,
+    q: &mut Query<(Entity, &mut Pawn, &mut Task, &mut Inventory)>,
+    at: IVec2,
+) {
+    if let Some(&pawn) = reservations.reserved_tiles.get(&at) {
+        reservations.release_pawn(pawn);
+        if let Ok((_, _, mut task, _)) = q.get_mut(pawn) {
+            *task = Task::Idle;
+        }
+    }
+}
+
+/// Releases a pawn's reservation when its task is replaced or removed, which
+/// includes the pawn being despawned.
+fn release_on_task_replace(replace: On<Replace, Task>, reservations: Option<ResMut<Reservations>>) {
+    if let Some(mut reservations) = reservations {
+        reservations.release_pawn(replace.entity);
+    }
+}
+
+/// Catches tasks changed in place outside the tick, e.g. from the console.
+pub fn release_abandoned_reservations(
+    q: Query<(Entity, &Task), Changed<Task>>,
     mut reservations: ResMut<Reservations>,
-    mut world_trees: ResMut<world::WorldTrees>,
 ) {
-    if sim.paused {
+    if reservations.by_pawn.is_empty() {
         return;
     }
 
-    let speed = sim.speed;
-    sim.tick.tick(time.delta().mul_f32(speed));
-    if !sim.tick.just_finished() {
+    for (entity, task) in &q {
+        if let Some(r) = reservations.by_pawn.get(&entity)
+            && task.target_tile() != Some(r.at)
+        {
+            reservations.release_pawn(entity);
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+fn check_reservations(
+    reservations: Res<Reservations>,
+    q: Query<&Task>,
+    mut reported: Local<bevy::platform::collections::HashSet<(IVec2, Entity)>>,
+) {
+    if !reservations.is_changed() {
         return;
     }
 
-    for (entity, mut pawn, mut transform, mut task, mut inv) in &mut q {
-        let next = match *task {
-            Task::Idle => {
-                pawn_tasks::handle_idle(entity, &pawn, &map, &mut reservations, &world_trees)
-            }
-            Task::GoToTree(at) => {
-                pawn_tasks::handle_go_to_tree(&mut pawn, &mut transform, at)
-            }
-            Task::Chop { at, progress } => {
-                pawn_tasks::handle_chop(
-                    entity,
-                    &mut map,
-                    &mut inv,
-                    at,
-                    progress,
-                    &mut reservations,
-                    &mut world_trees,
-                    &mut tile_entities,
-                    &mut q_tiles,
-                )
-            }
-            Task::GoToStockpile => pawn_tasks::handle_go_to_stockpile(&mut pawn, &mut transform),
-            Task::DropOff => pawn_tasks::handle_drop_off(&mut inv, &mut stockpile),
-        };
-
-        *task = next;
+    for leak in reservations.leaks(|pawn| q.get(pawn).ok().copied()) {
+        if reported.insert((leak.at, leak.pawn)) {
+            warn!(
+                "leaked reservation at ({},{}) held by {}: {}",
+                leak.at.x, leak.at.y, leak.pawn, leak.reason
+            );
+        }
     }
 }
@@ -0,0 +1,140 @@
+use bevy::asset::RenderAssetUsages;
+use bevy::image::ImageSampler;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::config::GameConfig;
+use crate::world::{self, CHUNK_SIZE, WorldMap};
+
+// texels per tile edge when tiles are drawn with a gap between them
+const GAP_TEXELS_PER_TILE: i32 = 8;
+
+#[derive(Component)]
+pub struct TileChunk(pub IVec2);
+
+#[derive(Resource)]
+pub struct TileChunks {
+    pub images: HashMap<IVec2, Handle<Image>>,
+    texels_per_tile: i32,
+    gap_texels: i32,
+}
+
+/// Draws the map as one textured sprite per chunk instead of one sprite per tile.
+pub fn spawn_tile_chunks(
+    commands: &mut Commands,
+    images: &mut Assets<Image>,
+    map: &mut WorldMap,
+    config: &GameConfig,
+) {
+    // without a gap one texel per tile is enough, which keeps large maps cheap
+    let (texels_per_tile, gap_texels) = if config.tile_gap > 0.0 {
+        let gap = (config.tile_gap / config.tile_size * GAP_TEXELS_PER_TILE as f32).round();
+        (GAP_TEXELS_PER_TILE, (gap as i32).max(1))
+    } else {
+        (1, 0)
+    };
+
+    let mut chunks = TileChunks {
+        images: HashMap::new(),
+        texels_per_tile,
+        gap_texels,
+    };
+
+    let count = map.chunk_count();
+    for cy in 0..count.y {
+        for cx in 0..count.x {
+            let chunk = IVec2::new(cx, cy);
+            let (min, size) = chunk_bounds(map, chunk);
+
+            let mut image = Image::new(
+                Extent3d {
+                    width: (size.x * texels_per_tile) as u32,
+                    height: (size.y * texels_per_tile) as u32,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                vec![0; (size.x * size.y * texels_per_tile * texels_per_tile * 4) as usize],
+                TextureFormat::Rgba8UnormSrgb,
+                RenderAssetUsages::default(),
+            );
+            image.sampler = ImageSampler::nearest();
+            if let Some(data) = image.data.as_mut() {
+                draw_chunk(data, map, &chunks, chunk);
+            }
+
+            let center = world::grid_to_world(config, min.x, min.y).truncate()
+                + (size - IVec2::ONE).as_vec2() * config.tile_size * 0.5;
+
+            let handle = images.add(image);
+            commands.spawn((
+                Sprite {
+                    image: handle.clone(),
+                    custom_size: Some(size.as_vec2() * config.tile_size),
+                    ..default()
+                },
+                Transform::from_translation(center.extend(0.0)),
+                TileChunk(chunk),
+            ));
+            chunks.images.insert(chunk, handle);
+        }
+    }
+
+    map.dirty_chunks.clear();
+    commands.insert_resource(chunks);
+}
+
+/// Redraws the textures of chunks whose tiles changed since the last frame.
+pub fn update_dirty_chunks(
+    mut map: ResMut<WorldMap>,
+    chunks: Res<TileChunks>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    if map.dirty_chunks.is_empty() {
+        return;
+    }
+
+    let dirty: Vec<IVec2> = map.dirty_chunks.drain().collect();
+    for chunk in dirty {
+        let Some(handle) = chunks.images.get(&chunk) else {
+            continue;
+        };
+        if let Some(image) = images.get_mut(handle)
+            && let Some(data) = image.data.as_mut()
+        {
+            draw_chunk(data, &map, &chunks, chunk);
+        }
+    }
+}
+
+fn chunk_bounds(map: &WorldMap, chunk: IVec2) -> (IVec2, IVec2) {
+    let min = chunk * CHUNK_SIZE;
+    let max = (min + IVec2::splat(CHUNK_SIZE)).min(IVec2::new(map.width, map.height));
+    (min, max - min)
+}
+
+fn draw_chunk(data: &mut [u8], map: &WorldMap, chunks: &TileChunks, chunk: IVec2) {
+    let (min, size) = chunk_bounds(map, chunk);
+    let texels = chunks.texels_per_tile;
+    let row_len = size.x * texels;
+
+    for ly in 0..size.y {
+        for lx in 0..size.x {
+            let tile = world::get(map, min.x + lx, min.y + ly);
+            let color = world::tile_color(tile).to_srgba().to_u8_array();
+
+            // image rows run top to bottom, map rows bottom to top
+            let top = (size.y - 1 - ly) * texels;
+            let left = lx * texels;
+
+            for ty in 0..texels {
+                for tx in 0..texels {
+                    let gap = tx >= texels - chunks.gap_texels || ty >= texels - chunks.gap_texels;
+                    let i = (((top + ty) * row_len + left + tx) * 4) as usize;
+                    let texel = if gap { [0; 4] } else { color };
+                    data[i..i + 4].copy_from_slice(&texel);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use crate::colony::{Colony, Item};
+use crate::config::GameConfig;
+use crate::creatures;
+use crate::rng::SimRng;
+use crate::world::{self, Path, WorldMap};
+
+/// How long a caravan stays open for business once it reaches the stockpile.
+pub const TRADE_TICKS: u32 = 240;
+/// Tiles covered per tick; pack animals are slow.
+const CARAVAN_SPEED: f32 = 0.5;
+/// Stock the trader can hold of an item before paying less than its base
+/// value for it. Anything scarcer is worth more to them.
+const NORMAL_SUPPLY: u32 = 20;
+
+/// Edge tiles tried before giving up on a caravan for the hour.
+const EDGE_TRIES: usize = 8;
+
+const CARAVAN_COLOR: Color = Color::srgb(0.85, 0.7, 0.2);
+
+/// Silver one unit is worth with nobody short of it or swimming in it.
+pub fn base_value(item: Item) -> u32 {
+    match item {
+        Item::Wood => 2,
+        Item::Stone => 3,
+        Item::Planks => 5,
+        Item::Blocks => 8,
+        Item::Silver => 1,
+        Item::Medicine => 30,
+        Item::Steel => 12,
+    }
+}
+
+/// What everything in the stockpile is worth at base value, in silver.
+pub fn wealth(colony: &Colony) -> u32 {
+    Item::ALL
+        .iter()
+        .map(|&item| colony.amount(item).saturating_mul(base_value(item)))
+        .fold(0, u32::saturating_add)
+}
+
+/// What one unit is worth to a trader already holding `supply` of it: double
+/// the base value when they have none, half once they hold three times the
+/// normal amount.
+pub fn value(item: Item, supply: u32) -> u32 {
+    (base_value(item) * 2 * NORMAL_SUPPLY)
+        .div_ceil(NORMAL_SUPPLY + supply)
+        .max(1)
+}
+
+/// Silver the colony pays for one unit. The trader takes a cut both ways.
+pub fn buy_price(item: Item, supply: u32) -> u32 {
+    (value(item, supply) * 5).div_ceil(4)
+}
+
+/// Silver the colony gets for one unit.
+pub fn sell_price(item: Item, supply: u32) -> u32 {
+    (value(item, supply) * 3 / 4).max(1)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaravanState {
+    Arriving,
+    Trading { ticks_left: u32 },
+    Leaving,
+    /// Off the map, waiting to be despawned.
+    Gone,
+}
+
+#[derive(Component, Debug, Clone)]
+pub struct Caravan {
+    pub x: i32,
+    pub y: i32,
+    /// Where it came in, and so where it heads back out.
+    pub exit: IVec2,
+    pub state: CaravanState,
+    pub effort: f32,
+    pub path: Path,
+    pub silver: u32,
+    pub stock: BTreeMap<Item, u32>,
+}
+
+impl Caravan {
+    pub fn pos(&self) -> IVec2 {
+        IVec2::new(self.x, self.y)
+    }
+
+    pub fn is_trading(&self) -> bool {
+        matches!(self.state, CaravanState::Trading { .. })
+    }
+
+    pub fn amount(&self, item: Item) -> u32 {
+        self.stock.get(&item).copied().unwrap_or(0)
+    }
+
+    /// Silver for `amount` of `item` bought a unit at a time, each dearer than
+    /// the last as the trader's stock runs down.
+    pub fn quote_buy(&self, item: Item, amount: u32) -> u32 {
+        let supply = self.amount(item);
+        (1..=amount.min(supply))
+            .map(|n| buy_price(item, supply - n))
+            .sum()
+    }
+
+    /// Silver for `amount` of `item` sold a unit at a time, each fetching less
+    /// than the last as the trader's stock fills up.
+    pub fn quote_sell(&self, item: Item, amount: u32) -> u32 {
+        let supply = self.amount(item);
+        (0..amount).map(|n| sell_price(item, supply + n)).sum()
+    }
+}
+
+/// Runs when a caravan is due. It comes in from a random edge tile that has a
+/// way through to the stockpile, and if none turns up nobody comes this time.
+/// Returns where it came in.
+pub fn caravan_arrives(
+    commands: &mut Commands,
+    map: &WorldMap,
+    config: &GameConfig,
+    rng: &mut SimRng,
+) -> Option<IVec2> {
+    let stockpile = config.stockpile();
+    let at = (0..EDGE_TRIES)
+        .filter_map(|_| creatures::edge_tile(map, rng))
+        .find(|&at| world::can_reach(map, at, stockpile, true))?;
+
+    let mut stock = BTreeMap::new();
+    stock.insert(Item::Medicine, 3 + rng.below(6));
+    stock.insert(Item::Steel, 20 + rng.below(21));
+    stock.insert(Item::Planks, rng.below(20));
+
+    commands.spawn(Caravan {
+        x: at.x,
+        y: at.y,
+        exit: at,
+        state: CaravanState::Arriving,
+        effort: 0.0,
+        path: Path::default(),
+        silver: 200 + rng.below(201),
+        stock,
+    });
+    Some(at)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaravanNews {
+    OpenForTrade,
+    Left,
+}
+
+/// Moves a caravan on by a tick: in to the stockpile, a while trading there,
+/// then back out the way it came.
+pub fn advance(caravan: &mut Caravan, map: &WorldMap, config: &GameConfig) -> Option<CaravanNews> {
+    match caravan.state {
+        CaravanState::Arriving => {
+            let stockpile = config.stockpile();
+            // should the way be cut off after it set out, it sets up shop
+            // wherever it got stuck
+            let arrived = (caravan.pos() - stockpile).abs().element_sum() <= 1;
+            if arrived || !step(caravan, map, stockpile) {
+                caravan.state = CaravanState::Trading {
+                    ticks_left: TRADE_TICKS,
+                };
+                return Some(CaravanNews::OpenForTrade);
+            }
+        }
+        CaravanState::Trading { ticks_left } => {
+            caravan.state = match ticks_left {
+                0 | 1 => CaravanState::Leaving,
+                _ => CaravanState::Trading {
+                    ticks_left: ticks_left - 1,
+                },
+            };
+        }
+        CaravanState::Leaving => {
+            let exit = caravan.exit;
+            if caravan.pos() == exit || !step(caravan, map, exit) {
+                caravan.state = CaravanState::Gone;
+                return Some(CaravanNews::Left);
+            }
+        }
+        CaravanState::Gone => {}
+    }
+    None
+}
+
+/// False when there's no way to `to` at all.
+fn step(caravan: &mut Caravan, map: &WorldMap, to: IVec2) -> bool {
+    let pos = caravan.pos();
+    let Some(next) = caravan.path.next_step(map, pos, to) else {
+        return false;
+    };
+    caravan.effort += CARAVAN_SPEED;
+    if caravan.effort >= 1.0 {
+        caravan.effort -= 1.0;
+        (caravan.x, caravan.y) = (next.x, next.y);
+    }
+    true
+}
+
+/// Buys from the caravan with the colony's silver. Returns what it cost.
+pub fn buy(
+    caravan: &mut Caravan,
+    colony: &mut Colony,
+    item: Item,
+    amount: u32,
+) -> Result<u32, String> {
+    if item == Item::Silver {
+        return Err("silver is what you pay with".to_string());
+    }
+    let have = caravan.amount(item);
+    if amount > have {
+        return Err(format!("the caravan only has {have} {}", item.name()));
+    }
+    let cost = caravan.quote_buy(item, amount);
+    if cost > colony.silver {
+        return Err(format!(
+            "that costs {cost} silver and you have {}",
+            colony.silver
+        ));
+    }
+
+    colony.silver -= cost;
+    *colony.amount_mut(item) += amount;
+    caravan.silver += cost;
+    *caravan.stock.entry(item).or_default() -= amount;
+    Ok(cost)
+}
+
+/// Sells from the stockpile to the caravan. Returns what it paid.
+pub fn sell(
+    caravan: &mut Caravan,
+    colony: &mut Colony,
+    item: Item,
+    amount: u32,
+) -> Result<u32, String> {
+    if item == Item::Silver {
+        return Err("silver is what you get paid in".to_string());
+    }
+    let have = colony.amount(item);
+    if amount > have {
+        return Err(format!("you only have {have} {}", item.name()));
+    }
+    let paid = caravan.quote_sell(item, amount);
+    if paid > caravan.silver {
+        return Err(format!(
+            "the caravan can't pay {paid} silver, it has {}",
+            caravan.silver
+        ));
+    }
+
+    *colony.amount_mut(item) -= amount;
+    colony.silver += paid;
+    caravan.silver -= paid;
+    *caravan.stock.entry(item).or_default() += amount;
+    Ok(paid)
+}
+
+pub fn attach_caravan_sprites(
+    mut commands: Commands,
+    config: Res<GameConfig>,
+    q: Query<Entity, Added<Caravan>>,
+) {
+    for entity in &q {
+        commands.entity(entity).insert((
+            Sprite {
+                color: CARAVAN_COLOR,
+                custom_size: Some(Vec2::new(config.tile_size * 1.2, config.tile_size * 0.8)),
+                ..default()
+            },
+            Transform::default(),
+        ));
+    }
+}
+
+pub fn update_caravan_sprites(
+    config: Res<GameConfig>,
+    mut q: Query<(&Caravan, &mut Transform), Changed<Caravan>>,
+) {
+    for (caravan, mut transform) in &mut q {
+        let pos = world::grid_to_world(&config, caravan.x, caravan.y);
+        transform.translation = pos.truncate().extend(2.0);
+    }
+}
@@ -1,31 +1,214 @@
 use bevy::prelude::*;
 
+use bevy::input::InputSystems;
 use bevy::window::PrimaryWindow;
 
-use crate::colony::Colony;
-use crate::config::TILE_SIZE;
+use crate::clock::ClockTime;
+use crate::colony::{Colony, Item};
+use crate::combat::Stance;
+use crate::config::GameConfig;
+use crate::console::{self, Console, ConsoleCommand};
+use crate::crafting::{self, BenchKind, Bill, Workbenches};
+use crate::events::{ColonyEvent, EventLog};
+use crate::health::Health;
+use crate::input::{self, Action, Actions, KeyBindings, Rebinding};
+use crate::mood::{self, Mood, Relationships};
 use crate::pawn::{Pawn, Task};
+use crate::pawn_tasks::{CHOP_TICKS, EXTINGUISH_TICKS, MINE_TICKS};
+use crate::pawn_render::PawnBatch;
+use crate::population::{self, HISTORY_HOURS, Population};
+use crate::scenario::{Outcome, Scenario};
+use crate::sim::{self, Reservations, Sim, SimSet};
+use crate::trade::{self, Caravan, CaravanState};
+use crate::weather::Weather;
+use crate::world::{self, MineDesignations, Tile, WorldMap};
 use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
 
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UiSet {
+    Input,
+    Display,
+}
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameConfig>()
+            .add_plugins(FrameTimeDiagnosticsPlugin::default())
+            .add_message::<ConsoleCommand>()
+            .configure_sets(
+                Update,
+                (
+                    UiSet::Input.before(SimSet::PreTick),
+                    UiSet::Display.after(SimSet::PostTick),
+                ),
+            )
+            .add_systems(Startup, setup_ui)
+            .add_systems(PreUpdate, input::update_actions.after(InputSystems))
+            .add_systems(
+                Update,
+                (
+                    (
+                        input::toggle_rebinding_screen,
+                        // after the console, so the press that sets a binding
+                        // isn't also taken as that binding being used
+                        input::capture_rebind
+                            .after(console::console_input)
+                            .before(handle_rebind_clicks),
+                        handle_rebind_clicks,
+                        console::console_input,
+                        console::run_console_commands.after(console::console_input),
+                        sim::sim_controls,
+                        select_pawn_on_click,
+                        cycle_bench_placement,
+                        place_bench_on_click.after(select_pawn_on_click),
+                        edit_bench_bill,
+                        toggle_mine_designation,
+                        handle_trade_clicks,
+                        scroll_event_log,
+                        dismiss_alerts,
+                    )
+                        .in_set(UiSet::Input),
+                    (
+                        update_key_bindings_ui,
+                        update_console_ui,
+                        update_scenario_ui,
+                        update_stock_ui,
+                        update_clock_ui,
+                        update_population_ui,
+                        update_trade_ui,
+                        update_fps_ui,
+                        update_pawn_ui,
+                        update_hovered_tile,
+                        update_tile_ui.after(update_hovered_tile),
+                        update_event_log_ui,
+                        spawn_alerts,
+                    )
+                        .in_set(UiSet::Display),
+                ),
+            );
+    }
+}
+
+fn setup_ui(mut commands: Commands) {
+    commands.spawn(Camera2d);
+
+    input::init(&mut commands);
+    spawn_ui(&mut commands);
+}
+
 #[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UiTextTag {
-    WoodValue,
+    Stock(Item),
+    ClockValue,
     FpsValue,
     PawnAction,
     PawnPosition,
     PawnId,
+    PawnHealth,
+    PawnMood,
+    PawnRelationships,
+    TileCoords,
+    TileKind,
+    TileReserved,
+    TileProgress,
+    TilePawns,
+    TileBuild,
+    EventLogLines,
+    ConsoleOutput,
+    ConsoleInput,
+    Binding(Action),
+    ScenarioTitle,
+    ScenarioGoals,
+    PopulationValue,
+    TradeHeader,
+    TradeRow(Item),
+    TradeStatus,
 }
 
 #[derive(Resource, Default)]
 pub struct SelectedPawn(pub Option<Entity>);
 
-const PAWN_COLOR: Color = Color::srgb(0.85, 0.85, 0.95);
-const PAWN_COLOR_SELECTED: Color = Color::srgb(1.0, 0.9, 0.4);
+#[derive(Resource, Default)]
+pub struct HoveredTile(pub Option<IVec2>);
+
+/// The bench the next click on the map builds, and how the last try went.
+#[derive(Resource, Default)]
+pub struct Placement {
+    pub bench: Option<BenchKind>,
+    pub status: String,
+}
+
+#[derive(Component)]
+pub struct TileTooltip;
+
+#[derive(Component)]
+pub struct AlertPanel;
+
+#[derive(Component)]
+pub struct Alert(pub ColonyEvent);
+
+#[derive(Component)]
+pub struct ConsolePanel;
+
+#[derive(Component)]
+pub struct KeyBindingsPanel;
+
+#[derive(Component)]
+pub struct RebindButton(pub Action);
+
+#[derive(Component)]
+pub struct ScenarioPanel;
+
+#[derive(Component)]
+pub struct TradePanel;
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TradeButton {
+    pub item: Item,
+    pub buy: bool,
+}
+
+/// The outcome of the last deal struck in the trade window.
+#[derive(Resource, Default)]
+pub struct TradeStatus(pub String);
+
+/// One column of the population graph; `0` is the oldest hour shown.
+#[derive(Component)]
+pub struct PopulationBar(pub usize);
+
+const TOOLTIP_OFFSET_PX: f32 = 16.0;
+const TOOLTIP_MAX_PAWNS: usize = 8;
+const INSPECTOR_RELATIONSHIPS: usize = 3;
+
+const LOG_VISIBLE_LINES: usize = 8;
+const ALERT_COLOR: Color = Color::srgba(0.55, 0.12, 0.10, 0.9);
+
+const CONSOLE_VISIBLE_LINES: usize = 12;
+
+const GRAPH_HEIGHT_PX: f32 = 60.0;
+const GRAPH_BAR_WIDTH_PX: f32 = 4.0;
+
+/// How much a shift-click in the trade window moves at once.
+const TRADE_BULK: u32 = 10;
 
 pub fn spawn_ui(commands: &mut Commands) {
     commands.insert_resource(SelectedPawn::default());
+    commands.insert_resource(HoveredTile::default());
+    commands.insert_resource(Placement::default());
+    commands.insert_resource(Console::default());
+    commands.insert_resource(TradeStatus::default());
     spawn_colony_ui(commands);
+    spawn_clock_ui(commands);
     spawn_pawn_ui(commands);
+    spawn_tile_ui(commands);
+    spawn_event_log_ui(commands);
+    spawn_console_ui(commands);
+    spawn_key_bindings_ui(commands);
+    spawn_scenario_ui(commands);
+    spawn_population_ui(commands);
+    spawn_trade_ui(commands);
 }
 
 pub fn spawn_colony_ui(commands: &mut Commands) {
@@ -46,7 +229,7 @@ pub fn spawn_colony_ui(commands: &mut Commands) {
 
     commands
         .spawn((
-            Text::new("Wood: "),
+            Text::new(""),
             TextFont {
                 font_size: 20.0,
                 ..default()
@@ -60,15 +243,26 @@ pub fn spawn_colony_ui(commands: &mut Commands) {
             },
         ))
         .with_children(|parent| {
-            parent.spawn((
-                TextSpan::new("0"),
-                TextFont {
-                    font_size: 20.0,
-                    ..default()
-                },
-                TextColor(Color::srgb(0.9, 0.8, 0.6)),
-                UiTextTag::WoodValue,
-            ));
+            for (i, item) in Item::ALL.into_iter().enumerate() {
+                let gap = if i == 0 { "" } else { "  " };
+                parent.spawn((
+                    TextSpan::new(format!("{gap}{item:?}: ")),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+                parent.spawn((
+                    TextSpan::new("0"),
+                    TextFont {
+                        font_size: 20.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.9, 0.8, 0.6)),
+                    UiTextTag::Stock(item),
+                ));
+            }
         });
 
     commands
@@ -99,6 +293,35 @@ pub fn spawn_colony_ui(commands: &mut Commands) {
         });
 }
 
+pub fn spawn_clock_ui(commands: &mut Commands) {
+    commands
+        .spawn((
+            Text::new(""),
+            TextFont {
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(8.0),
+                left: Val::Percent(45.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextSpan::new(""),
+                TextFont {
+                    font_size: 20.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.95, 0.9, 0.7)),
+                UiTextTag::ClockValue,
+            ));
+        });
+}
+
 pub fn spawn_pawn_ui(commands: &mut Commands) {
     commands.spawn((
         Text::new("Pawn Inspector"),
@@ -210,16 +433,613 @@ pub fn spawn_pawn_ui(commands: &mut Commands) {
                 UiTextTag::PawnAction,
             ));
         });
+
+    commands
+        .spawn((
+            Text::new("Health: "),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(200.0),
+                left: Val::Px(16.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextSpan::new("?"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.85, 1.0)),
+                UiTextTag::PawnHealth,
+            ));
+        });
+
+    // mood factors and relationships vary in length, so these two flow down
+    // a column instead of sitting at fixed offsets
+    commands
+        .spawn(Node {
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            top: Val::Px(224.0),
+            left: Val::Px(16.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            for (label, tag) in [
+                ("Mood: ", UiTextTag::PawnMood),
+                ("Relationships: ", UiTextTag::PawnRelationships),
+            ] {
+                parent
+                    .spawn((
+                        Text::new(label),
+                        TextFont {
+                            font_size: 18.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ))
+                    .with_children(|text| {
+                        text.spawn((
+                            TextSpan::new("?"),
+                            TextFont {
+                                font_size: 16.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.7, 0.85, 1.0)),
+                            tag,
+                        ));
+                    });
+            }
+        });
 }
 
-pub fn update_wood_ui(colony: Res<Colony>, mut q: Query<(&UiTextTag, &mut TextSpan)>) {
+pub fn spawn_tile_ui(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.85)),
+            GlobalZIndex(10),
+            TileTooltip,
+        ))
+        .with_children(|parent| {
+            spawn_tooltip_line(parent, "Tile: ", UiTextTag::TileCoords);
+            spawn_tooltip_line(parent, "Type: ", UiTextTag::TileKind);
+            spawn_tooltip_line(parent, "Reserved: ", UiTextTag::TileReserved);
+            spawn_tooltip_line(parent, "Progress: ", UiTextTag::TileProgress);
+            spawn_tooltip_line(parent, "Pawns: ", UiTextTag::TilePawns);
+            spawn_tooltip_line(parent, "Build: ", UiTextTag::TileBuild);
+        });
+}
+
+fn spawn_tooltip_line(parent: &mut ChildSpawnerCommands, label: &str, tag: UiTextTag) {
+    parent
+        .spawn((
+            Text::new(label),
+            TextFont {
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ))
+        .with_children(|line| {
+            line.spawn((
+                TextSpan::new("--"),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.85, 1.0)),
+                tag,
+            ));
+        });
+}
+
+pub fn spawn_event_log_ui(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                bottom: Val::Px(8.0),
+                left: Val::Px(8.0),
+                width: Val::Px(420.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.75)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Event Log (PgUp/PgDn)"),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            parent
+                .spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ))
+                .with_children(|text| {
+                    text.spawn((
+                        TextSpan::new(""),
+                        TextFont {
+                            font_size: 14.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                        UiTextTag::EventLogLines,
+                    ));
+                });
+        });
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..default()
+        },
+        AlertPanel,
+    ));
+}
+
+pub fn spawn_console_ui(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                top: Val::Px(0.0),
+                left: Val::Percent(20.0),
+                width: Val::Percent(60.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.02, 0.02, 0.04, 0.92)),
+            GlobalZIndex(20),
+            ConsolePanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ))
+                .with_children(|text| {
+                    text.spawn((
+                        TextSpan::new(""),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                        UiTextTag::ConsoleOutput,
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Text::new("> "),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.7, 0.95, 0.7)),
+                ))
+                .with_children(|text| {
+                    text.spawn((
+                        TextSpan::new(""),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.95, 0.7)),
+                        UiTextTag::ConsoleInput,
+                    ));
+                });
+        });
+}
+
+pub fn spawn_key_bindings_ui(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                top: Val::Percent(15.0),
+                left: Val::Percent(35.0),
+                width: Val::Percent(30.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.02, 0.02, 0.04, 0.92)),
+            GlobalZIndex(20),
+            KeyBindingsPanel,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Key Bindings (click to rebind, Esc to cancel)"),
+                TextFont {
+                    font_size: 18.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            for action in Action::ALL {
+                parent
+                    .spawn((
+                        Button,
+                        Node {
+                            padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                            ..default()
+                        },
+                        BackgroundColor(Color::srgba(0.15, 0.15, 0.2, 0.9)),
+                        RebindButton(action),
+                    ))
+                    .with_children(|button| {
+                        button
+                            .spawn((
+                                Text::new(format!("{action:?}: ")),
+                                TextFont {
+                                    font_size: 16.0,
+                                    ..default()
+                                },
+                                TextColor(Color::WHITE),
+                            ))
+                            .with_children(|text| {
+                                text.spawn((
+                                    TextSpan::new(""),
+                                    TextFont {
+                                        font_size: 16.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::srgb(0.7, 0.85, 1.0)),
+                                    UiTextTag::Binding(action),
+                                ));
+                            });
+                    });
+            }
+        });
+}
+
+pub fn spawn_population_ui(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                bottom: Val::Px(8.0),
+                right: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(6.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.05, 0.05, 0.08, 0.75)),
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Text::new("Population: "),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ))
+                .with_children(|text| {
+                    text.spawn((
+                        TextSpan::new("?"),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.85, 1.0)),
+                        UiTextTag::PopulationValue,
+                    ));
+                });
+
+            parent
+                .spawn(Node {
+                    align_items: AlignItems::FlexEnd,
+                    height: Val::Px(GRAPH_HEIGHT_PX),
+                    ..default()
+                })
+                .with_children(|graph| {
+                    for i in 0..HISTORY_HOURS {
+                        graph.spawn((
+                            Node {
+                                width: Val::Px(GRAPH_BAR_WIDTH_PX),
+                                height: Val::Px(0.0),
+                                ..default()
+                            },
+                            BackgroundColor(Color::srgb(0.45, 0.7, 0.95)),
+                            PopulationBar(i),
+                        ));
+                    }
+                });
+        });
+}
+
+pub fn spawn_trade_ui(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                top: Val::Percent(20.0),
+                left: Val::Percent(30.0),
+                width: Val::Percent(40.0),
+                padding: UiRect::all(Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.02, 0.02, 0.04, 0.92)),
+            GlobalZIndex(15),
+            TradePanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Text::new("Trade caravan (shift-click to trade 10): "),
+                    TextFont {
+                        font_size: 18.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ))
+                .with_children(|text| {
+                    text.spawn((
+                        TextSpan::new(""),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.7, 0.85, 1.0)),
+                        UiTextTag::TradeHeader,
+                    ));
+                });
+
+            for item in Item::ALL.into_iter().filter(|&item| item != Item::Silver) {
+                parent
+                    .spawn(Node {
+                        column_gap: Val::Px(6.0),
+                        align_items: AlignItems::Center,
+                        ..default()
+                    })
+                    .with_children(|row| {
+                        row.spawn((
+                            Text::new(format!("{}: ", item.name())),
+                            TextFont {
+                                font_size: 16.0,
+                                ..default()
+                            },
+                            TextColor(Color::WHITE),
+                            Node {
+                                flex_grow: 1.0,
+                                ..default()
+                            },
+                        ))
+                        .with_children(|text| {
+                            text.spawn((
+                                TextSpan::new(""),
+                                TextFont {
+                                    font_size: 16.0,
+                                    ..default()
+                                },
+                                TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                                UiTextTag::TradeRow(item),
+                            ));
+                        });
+
+                        for (label, buy) in [("Buy", true), ("Sell", false)] {
+                            row.spawn((
+                                Button,
+                                Node {
+                                    padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                                    ..default()
+                                },
+                                BackgroundColor(Color::srgba(0.15, 0.15, 0.2, 0.9)),
+                                TradeButton { item, buy },
+                            ))
+                            .with_children(|button| {
+                                button.spawn((
+                                    Text::new(label),
+                                    TextFont {
+                                        font_size: 16.0,
+                                        ..default()
+                                    },
+                                    TextColor(Color::WHITE),
+                                ));
+                            });
+                        }
+                    });
+            }
+
+            parent
+                .spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ))
+                .with_children(|text| {
+                    text.spawn((
+                        TextSpan::new(""),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(1.0, 0.8, 0.5)),
+                        UiTextTag::TradeStatus,
+                    ));
+                });
+        });
+}
+
+pub fn spawn_scenario_ui(commands: &mut Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                display: Display::None,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(8.0),
+                top: Val::Percent(30.0),
+                left: Val::Percent(30.0),
+                width: Val::Percent(40.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.02, 0.02, 0.04, 0.95)),
+            GlobalZIndex(30),
+            ScenarioPanel,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 24.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ))
+                .with_children(|text| {
+                    text.spawn((
+                        TextSpan::new(""),
+                        TextFont {
+                            font_size: 24.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                        UiTextTag::ScenarioTitle,
+                    ));
+                });
+
+            parent
+                .spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ))
+                .with_children(|text| {
+                    text.spawn((
+                        TextSpan::new(""),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.8, 0.8, 0.8)),
+                        UiTextTag::ScenarioGoals,
+                    ));
+                });
+        });
+}
+
+pub fn update_stock_ui(colony: Res<Colony>, mut q: Query<(&UiTextTag, &mut TextSpan)>) {
     if !colony.is_changed() {
         return;
     }
 
     for (tag, mut text) in &mut q {
-        if *tag == UiTextTag::WoodValue {
-            text.0 = colony.wood.to_string();
+        if let UiTextTag::Stock(item) = *tag {
+            text.0 = colony.amount(item).to_string();
+        }
+    }
+}
+
+pub fn update_population_ui(
+    population: Res<Population>,
+    config: Res<GameConfig>,
+    map: Res<WorldMap>,
+    mut q_bars: Query<(&PopulationBar, &mut Node)>,
+    mut q_text: Query<(&UiTextTag, &mut TextSpan)>,
+) {
+    if !population.is_changed() {
+        return;
+    }
+
+    // scaled against the bed count so the graph shows how close to full we are
+    let beds = population::beds(&map, &config);
+    let scale = GRAPH_HEIGHT_PX / beds.max(1) as f32;
+    let offset = HISTORY_HOURS - population.history.len();
+    for (bar, mut node) in &mut q_bars {
+        let count = match bar.0.checked_sub(offset) {
+            Some(i) => population.history[i],
+            None => 0,
+        };
+        node.height = Val::Px((count as f32 * scale).min(GRAPH_HEIGHT_PX));
+    }
+
+    let value = format!("{} / {beds} beds", population.current());
+    for (tag, mut text) in &mut q_text {
+        if *tag == UiTextTag::PopulationValue {
+            text.0 = value.clone();
+            break;
+        }
+    }
+}
+
+pub fn update_clock_ui(
+    sim: Res<Sim>,
+    config: Res<GameConfig>,
+    weather: Res<Weather>,
+    mut q: Query<(&UiTextTag, &mut TextSpan)>,
+) {
+    if !sim.is_changed() && !weather.is_changed() {
+        return;
+    }
+
+    let time = ClockTime::at(sim.ticks, config.ticks_per_hour);
+    let night = if time.is_night() { " (night)" } else { "" };
+    let clock_value = format!(
+        "{}{night} - {}, {}",
+        time.describe(),
+        weather.season.name(),
+        weather.kind.name()
+    );
+
+    for (tag, mut text) in &mut q {
+        if *tag == UiTextTag::ClockValue {
+            text.0 = clock_value.clone();
             break;
         }
     }
@@ -248,81 +1068,612 @@ pub fn update_fps_ui(
 
 pub fn update_pawn_ui(
     selected: Res<SelectedPawn>,
-    q_pawns: Query<(&Pawn, &Task)>,
+    config: Res<GameConfig>,
+    map: Res<WorldMap>,
+    weather: Res<Weather>,
+    benches: Res<Workbenches>,
+    q_pawns: Query<(&Pawn, &Task, &Stance, &Health, &Mood, &Relationships)>,
     mut q_text: Query<(&UiTextTag, &mut TextSpan)>,
 ) {
-    let (action_value, position_value, id_value) = match selected.0 {
-        Some(entity) => match q_pawns.get(entity) {
-            Ok((pawn, task)) => (
-                format_task(task),
-                format!("({},{})", pawn.x, pawn.y),
-                pawn.id.to_string(),
-            ),
-            Err(_) => ("None".to_string(), "(?,?)".to_string(), "?".to_string()),
-        },
-        None => ("None".to_string(), "(?,?)".to_string(), "?".to_string()),
+    let unknown = || {
+        (
+            "None".to_string(),
+            "(?,?)".to_string(),
+            "?".to_string(),
+            "?".to_string(),
+            "?".to_string(),
+            "?".to_string(),
+        )
     };
+    let (action_value, position_value, id_value, health_value, mood_value, relationships_value) =
+        match selected.0 {
+            Some(entity) => match q_pawns.get(entity) {
+                Ok((pawn, task, stance, health, mood, relationships)) => (
+                    match stance {
+                        Stance::Work => format_task(task, &benches),
+                        Stance::Fight(_) => "Fighting".to_string(),
+                        Stance::Flee(_) => "Fleeing".to_string(),
+                    },
+                    format!("({},{})", pawn.x, pawn.y),
+                    pawn.id.to_string(),
+                    format!("{}/{}", health.current, health.max),
+                    format_mood(
+                        mood,
+                        &mood::factors(pawn, health, mood, &map, weather.kind, &config),
+                    ),
+                    format_relationships(relationships),
+                ),
+                Err(_) => unknown(),
+            },
+            None => unknown(),
+        };
 
     for (tag, mut text) in &mut q_text {
         match *tag {
             UiTextTag::PawnAction => text.0 = action_value.clone(),
             UiTextTag::PawnPosition => text.0 = position_value.clone(),
             UiTextTag::PawnId => text.0 = id_value.clone(),
-            UiTextTag::WoodValue => {},
-            UiTextTag::FpsValue => {},
+            UiTextTag::PawnHealth => text.0 = health_value.clone(),
+            UiTextTag::PawnMood => text.0 = mood_value.clone(),
+            UiTextTag::PawnRelationships => text.0 = relationships_value.clone(),
+            _ => {}
         }
     }
 }
 
-fn format_task(task: &Task) -> String {
+fn format_mood(mood: &Mood, factors: &[(&str, i32)]) -> String {
+    let mut out = mood.value.to_string();
+    for (label, effect) in factors {
+        out.push_str(&format!("\n  {label} {effect:+}"));
+    }
+    out
+}
+
+fn format_relationships(relationships: &Relationships) -> String {
+    let top = relationships.strongest(INSPECTOR_RELATIONSHIPS);
+    if top.is_empty() {
+        return "none yet".to_string();
+    }
+    top.iter()
+        .map(|(pawn, opinion)| format!("\n  Pawn {pawn} {opinion:+}"))
+        .collect()
+}
+
+/// How far through `ticks` of work `progress` is, in percent.
+fn percent(progress: u8, ticks: u8) -> u32 {
+    progress as u32 * 100 / ticks.max(1) as u32
+}
+
+fn format_task(task: &Task, benches: &Workbenches) -> String {
     match *task {
         Task::Idle => "Idle".to_string(),
         Task::GoToTree(at) => format!("GoToTree ({},{})", at.x, at.y),
         Task::Chop { at, progress } => {
-            format!("Chop ({},{}) {} %", at.x, at.y, progress.saturating_mul(10))
+            format!("Chop ({},{}) {} %", at.x, at.y, percent(progress, CHOP_TICKS))
         }
+        Task::GoToRock(at) => format!("GoToRock ({},{})", at.x, at.y),
+        Task::Mine { at, progress } => {
+            format!("Mine ({},{}) {} %", at.x, at.y, percent(progress, MINE_TICKS))
+        }
+        Task::FetchInputs(at) => format!("FetchInputs for ({},{})", at.x, at.y),
+        Task::GoToBench(at) => format!("GoToBench ({},{})", at.x, at.y),
+        Task::Craft { at, progress } => match benches.0.get(&at) {
+            Some(bench) => {
+                let done = percent(progress, bench.kind.recipe().work_ticks);
+                format!("Craft ({},{}) {done} %", at.x, at.y)
+            }
+            None => format!("Craft ({},{})", at.x, at.y),
+        },
         Task::GoToStockpile => "GoToStockpile".to_string(),
         Task::DropOff => "DropOff".to_string(),
+        Task::GoToFire(at) => format!("GoToFire ({},{})", at.x, at.y),
+        Task::Extinguish { at, progress } => {
+            let done = percent(progress, EXTINGUISH_TICKS);
+            format!("Extinguish ({},{}) {done} %", at.x, at.y)
+        }
+        Task::GoToPickup(at) => format!("GoToPickup ({},{})", at.x, at.y),
+        Task::Rest { .. } => "Resting".to_string(),
+        Task::Break { .. } => "On a break".to_string(),
     }
 }
 
-pub fn select_pawn_on_click(
-    buttons: Res<ButtonInput<MouseButton>>,
-    windows: Query<&Window, With<PrimaryWindow>>,
-    cameras: Query<(&Camera, &GlobalTransform)>,
-    q_pawns: Query<(Entity, &Transform), With<Pawn>>,
-    mut selected: ResMut<SelectedPawn>,
+pub fn scroll_event_log(actions: Res<Actions>, mut log: ResMut<EventLog>) {
+    let max_scroll = log.entries.len().saturating_sub(LOG_VISIBLE_LINES);
+
+    if actions.just_pressed(Action::ScrollLogUp) {
+        log.scroll = (log.scroll + LOG_VISIBLE_LINES).min(max_scroll);
+    } else if actions.just_pressed(Action::ScrollLogDown) {
+        log.scroll = log.scroll.saturating_sub(LOG_VISIBLE_LINES);
+    }
+}
+
+pub fn update_event_log_ui(log: Res<EventLog>, mut q: Query<(&UiTextTag, &mut TextSpan)>) {
+    if !log.is_changed() {
+        return;
+    }
+
+    let end = log.entries.len().saturating_sub(log.scroll);
+    let start = end.saturating_sub(LOG_VISIBLE_LINES);
+    let lines: Vec<&str> = log
+        .entries
+        .range(start..end)
+        .map(|entry| entry.as_str())
+        .collect();
+
+    for (tag, mut text) in &mut q {
+        if *tag == UiTextTag::EventLogLines {
+            text.0 = lines.join("\n");
+            break;
+        }
+    }
+}
+
+pub fn spawn_alerts(
+    mut commands: Commands,
+    mut events: MessageReader<ColonyEvent>,
+    q_panel: Query<Entity, With<AlertPanel>>,
+    q_alerts: Query<&Alert>,
+) {
+    let panel = match q_panel.single() {
+        Ok(panel) => panel,
+        Err(_) => return,
+    };
+
+    let mut shown: Vec<ColonyEvent> = q_alerts.iter().map(|alert| alert.0).collect();
+
+    for event in events.read() {
+        if !event.is_alert() || shown.contains(event) {
+            continue;
+        }
+        shown.push(*event);
+
+        commands.entity(panel).with_children(|parent| {
+            parent
+                .spawn((
+                    Button,
+                    Node {
+                        padding: UiRect::all(Val::Px(6.0)),
+                        ..default()
+                    },
+                    BackgroundColor(ALERT_COLOR),
+                    Alert(*event),
+                ))
+                .with_children(|button| {
+                    button.spawn((
+                        Text::new(format!("{}  [x]", event.describe())),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::WHITE),
+                    ));
+                });
+        });
+    }
+}
+
+pub fn dismiss_alerts(
+    mut commands: Commands,
+    q_alerts: Query<(Entity, Ref<Interaction>), With<Alert>>,
 ) {
-    if !buttons.just_pressed(MouseButton::Left) {
+    for (entity, interaction) in &q_alerts {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn update_scenario_ui(
+    scenario: Option<Res<Scenario>>,
+    mut q_panel: Query<&mut Node, With<ScenarioPanel>>,
+    mut q_text: Query<(&UiTextTag, &mut TextSpan)>,
+) {
+    let Some(scenario) = scenario else {
+        return;
+    };
+    if !scenario.is_changed() {
         return;
     }
 
+    let Some(outcome) = scenario.outcome else {
+        return;
+    };
+
+    if let Ok(mut node) = q_panel.single_mut() {
+        node.display = Display::Flex;
+    }
+
+    let title_value = match outcome {
+        Outcome::Won => format!("Scenario won: {}", scenario.name),
+        Outcome::Lost => format!("Scenario lost: {}", scenario.name),
+    };
+    let goals_value = scenario
+        .goals
+        .iter()
+        .map(|progress| match progress.met_at {
+            Some(tick) => format!("[x] {} (tick {tick})", progress.goal.describe()),
+            None => format!("[ ] {}", progress.goal.describe()),
+        })
+        .chain(std::iter::once(format!(
+            "Delivered: {} wood, {} stone",
+            scenario.delivered.wood, scenario.delivered.stone
+        )))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    for (tag, mut text) in &mut q_text {
+        match *tag {
+            UiTextTag::ScenarioTitle => text.0 = title_value.clone(),
+            UiTextTag::ScenarioGoals => text.0 = goals_value.clone(),
+            _ => {}
+        }
+    }
+}
+
+pub fn update_console_ui(
+    console: Res<Console>,
+    mut q_panel: Query<&mut Node, With<ConsolePanel>>,
+    mut q_text: Query<(&UiTextTag, &mut TextSpan)>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+
+    if let Ok(mut node) = q_panel.single_mut() {
+        node.display = if console.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    let start = console.output.len().saturating_sub(CONSOLE_VISIBLE_LINES);
+    let lines: Vec<&str> = console
+        .output
+        .range(start..)
+        .map(|line| line.as_str())
+        .collect();
+    let output_value = lines.join("\n");
+    let input_value = format!("{}_", console.input);
+
+    for (tag, mut text) in &mut q_text {
+        match *tag {
+            UiTextTag::ConsoleOutput => text.0 = output_value.clone(),
+            UiTextTag::ConsoleInput => text.0 = input_value.clone(),
+            _ => {}
+        }
+    }
+}
+
+pub fn handle_rebind_clicks(
+    mut rebinding: ResMut<Rebinding>,
+    q_buttons: Query<(&RebindButton, Ref<Interaction>)>,
+) {
+    if !rebinding.open {
+        return;
+    }
+
+    for (button, interaction) in &q_buttons {
+        if interaction.is_changed() && *interaction == Interaction::Pressed {
+            rebinding.waiting = Some(button.0);
+        }
+    }
+}
+
+pub fn update_key_bindings_ui(
+    bindings: Res<KeyBindings>,
+    rebinding: Res<Rebinding>,
+    mut q_panel: Query<&mut Node, With<KeyBindingsPanel>>,
+    mut q_text: Query<(&UiTextTag, &mut TextSpan)>,
+) {
+    if !bindings.is_changed() && !rebinding.is_changed() {
+        return;
+    }
+
+    if let Ok(mut node) = q_panel.single_mut() {
+        node.display = if rebinding.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+
+    for (tag, mut text) in &mut q_text {
+        if let UiTextTag::Binding(action) = *tag {
+            text.0 = if rebinding.waiting == Some(action) {
+                "press a key...".to_string()
+            } else {
+                match bindings.get(action) {
+                    Some(binding) => binding.label(),
+                    None => "unbound".to_string(),
+                }
+            };
+        }
+    }
+}
+
+/// Buys or sells one unit per click, or `TRADE_BULK` with shift held.
+pub fn handle_trade_clicks(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut colony: ResMut<Colony>,
+    mut status: ResMut<TradeStatus>,
+    mut events: MessageWriter<ColonyEvent>,
+    mut q_caravans: Query<&mut Caravan>,
+    q_buttons: Query<(&TradeButton, Ref<Interaction>)>,
+) {
+    let Some(mut caravan) = q_caravans.iter_mut().find(|c| c.is_trading()) else {
+        return;
+    };
+    let amount = if keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        TRADE_BULK
+    } else {
+        1
+    };
+
+    for (button, interaction) in &q_buttons {
+        if !interaction.is_changed() || *interaction != Interaction::Pressed {
+            continue;
+        }
+        let TradeButton { item, buy } = *button;
+        let result = if buy {
+            trade::buy(&mut caravan, &mut colony, item, amount)
+        } else {
+            trade::sell(&mut caravan, &mut colony, item, amount)
+        };
+        match result {
+            Ok(silver) => {
+                let event = ColonyEvent::Traded {
+                    bought: buy,
+                    item,
+                    amount,
+                    silver,
+                };
+                status.0 = event.describe();
+                events.write(event);
+            }
+            Err(err) => status.0 = format!("No deal: {err}"),
+        }
+    }
+}
+
+pub fn update_trade_ui(
+    colony: Res<Colony>,
+    mut status: ResMut<TradeStatus>,
+    q_caravans: Query<&Caravan>,
+    mut q_panel: Query<&mut Node, With<TradePanel>>,
+    mut q_text: Query<(&UiTextTag, &mut TextSpan)>,
+) {
+    let caravan = q_caravans.iter().find(|c| c.is_trading());
+
+    if let Ok(mut node) = q_panel.single_mut() {
+        let display = if caravan.is_some() {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        if node.display != display {
+            node.display = display;
+        }
+    }
+
+    let Some(caravan) = caravan else {
+        if !status.0.is_empty() {
+            status.0.clear();
+        }
+        return;
+    };
+    let ticks_left = match caravan.state {
+        CaravanState::Trading { ticks_left } => ticks_left,
+        _ => 0,
+    };
+
+    for (tag, mut text) in &mut q_text {
+        let value = match *tag {
+            UiTextTag::TradeHeader => format!(
+                "leaving in {ticks_left} ticks, you have {} silver, they have {}",
+                colony.silver, caravan.silver
+            ),
+            UiTextTag::TradeRow(item) => {
+                let buy = match caravan.amount(item) {
+                    0 => "-".to_string(),
+                    _ => caravan.quote_buy(item, 1).to_string(),
+                };
+                format!(
+                    "you {} / them {}, buy {buy} sell {}",
+                    colony.amount(item),
+                    caravan.amount(item),
+                    caravan.quote_sell(item, 1)
+                )
+            }
+            UiTextTag::TradeStatus => status.0.clone(),
+            _ => continue,
+        };
+        if text.0 != value {
+            text.0 = value;
+        }
+    }
+}
+
+pub fn update_hovered_tile(
+    config: Res<GameConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut hovered: ResMut<HoveredTile>,
+    mut q_tooltip: Query<&mut Node, With<TileTooltip>>,
+) {
     let window = match windows.single() {
         Ok(window) => window,
         Err(_) => return,
     };
 
-    let cursor_pos = match window.cursor_position() {
-        Some(pos) => pos,
+    let tile =
+        cursor_world_pos(window, &cameras).and_then(|pos| world::world_to_grid(&config, pos));
+    if hovered.0 != tile {
+        hovered.0 = tile;
+    }
+
+    let mut node = match q_tooltip.single_mut() {
+        Ok(node) => node,
+        Err(_) => return,
+    };
+
+    match (tile, window.cursor_position()) {
+        (Some(_), Some(cursor)) => {
+            node.display = Display::Flex;
+            node.left = Val::Px(cursor.x + TOOLTIP_OFFSET_PX);
+            node.top = Val::Px(cursor.y + TOOLTIP_OFFSET_PX);
+        }
+        _ => node.display = Display::None,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn update_tile_ui(
+    hovered: Res<HoveredTile>,
+    map: Res<WorldMap>,
+    designations: Res<MineDesignations>,
+    benches: Res<Workbenches>,
+    placement: Res<Placement>,
+    reservations: Res<Reservations>,
+    q_pawns: Query<(&Pawn, &Task)>,
+    mut q_text: Query<(&UiTextTag, &mut TextSpan)>,
+) {
+    let at = match hovered.0 {
+        Some(at) => at,
         None => return,
     };
 
-    let (camera, camera_transform) = match cameras.single() {
-        Ok(camera) => camera,
-        Err(_) => return,
+    let tile = world::get(&map, at.x, at.y);
+
+    let reserved_value = match reservations.reserved_tiles.get(&at) {
+        Some(&entity) => match q_pawns.get(entity) {
+            Ok((pawn, _)) => format!("Yes (pawn {})", pawn.id),
+            Err(_) => "Yes (?)".to_string(),
+        },
+        None => "No".to_string(),
+    };
+
+    let mut progress_value = "--".to_string();
+    let mut pawn_ids = Vec::new();
+    for (pawn, task) in &q_pawns {
+        if pawn.x == at.x && pawn.y == at.y {
+            pawn_ids.push(pawn.id);
+        }
+        match *task {
+            Task::Chop {
+                at: work_at,
+                progress,
+            } if work_at == at => {
+                progress_value = format!("Chop {} %", percent(progress, CHOP_TICKS));
+            }
+            Task::Mine {
+                at: work_at,
+                progress,
+            } if work_at == at => {
+                progress_value = format!("Mine {} %", percent(progress, MINE_TICKS));
+            }
+            _ => {}
+        }
+    }
+
+    let coords_value = format!("({},{})", at.x, at.y);
+    let kind_value = if designations.0.contains(&at) {
+        format!("{tile:?} (marked for mining)")
+    } else if let Some(bench) = benches.0.get(&at) {
+        format!("{tile:?} ({})", bench.describe())
+    } else {
+        format!("{tile:?}")
+    };
+    let pawns_value = format_pawn_ids(&mut pawn_ids);
+    let build_value = match placement.bench {
+        _ if !placement.status.is_empty() => placement.status.clone(),
+        Some(kind) => format!("{} (click to place)", kind.name()),
+        None => "--".to_string(),
+    };
+
+    for (tag, mut text) in &mut q_text {
+        match *tag {
+            UiTextTag::TileCoords => text.0 = coords_value.clone(),
+            UiTextTag::TileKind => text.0 = kind_value.clone(),
+            UiTextTag::TileReserved => text.0 = reserved_value.clone(),
+            UiTextTag::TileProgress => text.0 = progress_value.clone(),
+            UiTextTag::TilePawns => text.0 = pawns_value.clone(),
+            UiTextTag::TileBuild => text.0 = build_value.clone(),
+            _ => {}
+        }
+    }
+}
+
+fn format_pawn_ids(pawn_ids: &mut [u32]) -> String {
+    if pawn_ids.is_empty() {
+        return "None".to_string();
+    }
+
+    pawn_ids.sort_unstable();
+    let shown: Vec<String> = pawn_ids
+        .iter()
+        .take(TOOLTIP_MAX_PAWNS)
+        .map(|id| id.to_string())
+        .collect();
+
+    let hidden = pawn_ids.len().saturating_sub(TOOLTIP_MAX_PAWNS);
+    if hidden > 0 {
+        format!("{} (+{hidden} more)", shown.join(", "))
+    } else {
+        shown.join(", ")
+    }
+}
+
+fn cursor_world_pos(
+    window: &Window,
+    cameras: &Query<(&Camera, &GlobalTransform)>,
+) -> Option<Vec2> {
+    let cursor_pos = window.cursor_position()?;
+    let (camera, camera_transform) = cameras.single().ok()?;
+    camera.viewport_to_world_2d(camera_transform, cursor_pos).ok()
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn select_pawn_on_click(
+    actions: Res<Actions>,
+    config: Res<GameConfig>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    batch: Option<Res<PawnBatch>>,
+    placement: Res<Placement>,
+    q_interactions: Query<&Interaction>,
+    mut selected: ResMut<SelectedPawn>,
+) {
+    // while a bench is being placed the click is for that instead
+    if !actions.just_pressed(Action::Select) || placement.bench.is_some() {
+        return;
+    }
+
+    // clicks on UI buttons shouldn't reach the map
+    if q_interactions.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+
+    // nothing to pick from when pawns aren't being drawn
+    let Some(batch) = batch else {
+        return;
     };
 
-    let world_pos = match camera.viewport_to_world_2d(camera_transform, cursor_pos) {
-        Ok(pos) => pos,
+    let window = match windows.single() {
+        Ok(window) => window,
         Err(_) => return,
     };
 
+    let world_pos = match cursor_world_pos(window, &cameras) {
+        Some(pos) => pos,
+        None => return,
+    };
+
     let mut best: Option<(f32, Entity)> = None;
-    let radius = TILE_SIZE * 0.5;
+    let radius = config.tile_size * 0.5;
     let radius_sq = radius * radius;
 
-    for (entity, transform) in &q_pawns {
-        let pawn_pos = transform.translation.truncate();
+    for (&entity, pawn_pos) in batch.entities.iter().zip(&batch.positions) {
         let dist_sq = pawn_pos.distance_squared(world_pos);
         if dist_sq <= radius_sq {
             match best {
@@ -336,19 +1687,92 @@ pub fn select_pawn_on_click(
     selected.0 = best.map(|(_, entity)| entity);
 }
 
-pub fn update_selected_pawn_visuals(
-    selected: Res<SelectedPawn>,
-    mut q_pawns: Query<(Entity, &mut Sprite), With<Pawn>>,
+/// Marks the rock under the cursor for mining, or clears the mark if it's
+/// already there.
+pub fn toggle_mine_designation(
+    actions: Res<Actions>,
+    hovered: Res<HoveredTile>,
+    map: Res<WorldMap>,
+    mut designations: ResMut<MineDesignations>,
 ) {
-    if !selected.is_changed() {
+    if !actions.just_pressed(Action::DesignateMine) {
         return;
     }
+    let Some(at) = hovered.0 else {
+        return;
+    };
 
-    for (entity, mut sprite) in &mut q_pawns {
-        sprite.color = if Some(entity) == selected.0 {
-            PAWN_COLOR_SELECTED
-        } else {
-            PAWN_COLOR
-        };
+    if !designations.0.remove(&at) && world::get(&map, at.x, at.y) == Tile::Rock {
+        designations.0.insert(at);
+    }
+}
+
+/// Steps through what a click on the map will build: a sawmill, then a
+/// stonecutter, then nothing.
+pub fn cycle_bench_placement(actions: Res<Actions>, mut placement: ResMut<Placement>) {
+    if !actions.just_pressed(Action::PlaceBench) {
+        return;
     }
+
+    placement.bench = match placement.bench {
+        None => Some(BenchKind::Sawmill),
+        Some(BenchKind::Sawmill) => Some(BenchKind::Stonecutter),
+        Some(BenchKind::Stonecutter) => None,
+    };
+    placement.status.clear();
+}
+
+/// Builds the chosen bench on the clicked tile, paying for it out of the
+/// stockpile.
+pub fn place_bench_on_click(
+    actions: Res<Actions>,
+    hovered: Res<HoveredTile>,
+    q_interactions: Query<&Interaction>,
+    mut map: ResMut<WorldMap>,
+    mut benches: ResMut<Workbenches>,
+    mut colony: ResMut<Colony>,
+    mut placement: ResMut<Placement>,
+) {
+    if !actions.just_pressed(Action::Select) {
+        return;
+    }
+    let (Some(kind), Some(at)) = (placement.bench, hovered.0) else {
+        return;
+    };
+    if q_interactions.iter().any(|i| *i != Interaction::None) {
+        return;
+    }
+
+    match crafting::place(&mut map, &mut benches, &mut colony, kind, at) {
+        Ok(()) => {
+            placement.bench = None;
+            placement.status = format!("built a {} at ({},{})", kind.name(), at.x, at.y);
+        }
+        Err(err) => placement.status = format!("can't build there: {err}"),
+    }
+}
+
+/// Queues one more batch on the bench under the cursor, or clears its bill.
+pub fn edit_bench_bill(
+    actions: Res<Actions>,
+    hovered: Res<HoveredTile>,
+    mut benches: ResMut<Workbenches>,
+) {
+    let queue = actions.just_pressed(Action::QueueBatch);
+    let clear = actions.just_pressed(Action::ClearBill);
+    if !queue && !clear {
+        return;
+    }
+    let Some(bench) = hovered.0.and_then(|at| benches.0.get_mut(&at)) else {
+        return;
+    };
+
+    bench.bill = if clear {
+        None
+    } else {
+        match bench.bill {
+            Some(Bill::Make(count)) => Some(Bill::Make(count.saturating_add(1))),
+            _ => Some(Bill::Make(1)),
+        }
+    };
 }
@@ -0,0 +1,254 @@
+use bevy::prelude::*;
+
+use crate::config::GameConfig;
+use crate::rng::SimRng;
+use crate::world::{self, Tile, WorldMap, WorldTrees};
+
+const RAIN_COLOR: Color = Color::srgba(0.6, 0.7, 1.0, 0.6);
+const SNOW_COLOR: Color = Color::srgba(1.0, 1.0, 1.0, 0.85);
+const MAX_PARTICLES: usize = 2000;
+
+const NEIGHBOURS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub fn of_day(day: u64, days_per_season: u32) -> Self {
+        match ((day - 1) / days_per_season as u64) % 4 {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Season::Spring => "Spring",
+            Season::Summer => "Summer",
+            Season::Autumn => "Autumn",
+            Season::Winter => "Winter",
+        }
+    }
+
+    pub fn growth(&self) -> f32 {
+        match self {
+            Season::Spring => 1.5,
+            Season::Summer => 1.0,
+            Season::Autumn => 0.5,
+            Season::Winter => 0.0,
+        }
+    }
+
+    /// Relative odds of clear, rain and snow.
+    fn weather_odds(&self) -> [u32; 3] {
+        match self {
+            Season::Spring => [60, 40, 0],
+            Season::Summer => [80, 20, 0],
+            Season::Autumn => [55, 45, 0],
+            Season::Winter => [50, 10, 40],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeatherKind {
+    Clear,
+    Rain,
+    Snow,
+}
+
+impl WeatherKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            WeatherKind::Clear => "clear",
+            WeatherKind::Rain => "rain",
+            WeatherKind::Snow => "snow",
+        }
+    }
+
+    /// Fraction of a tile a pawn covers per tick.
+    pub fn move_speed(&self) -> f32 {
+        match self {
+            WeatherKind::Clear => 1.0,
+            WeatherKind::Rain => 0.75,
+            WeatherKind::Snow => 0.5,
+        }
+    }
+
+    /// Fraction of a chop a pawn gets done per tick.
+    pub fn chop_speed(&self) -> f32 {
+        match self {
+            WeatherKind::Clear => 1.0,
+            WeatherKind::Rain => 0.8,
+            WeatherKind::Snow => 0.6,
+        }
+    }
+
+    pub fn growth(&self) -> f32 {
+        match self {
+            WeatherKind::Clear => 1.0,
+            WeatherKind::Rain => 2.0,
+            WeatherKind::Snow => 0.0,
+        }
+    }
+
+    pub fn fire_spread(&self) -> f32 {
+        match self {
+            WeatherKind::Clear => 1.0,
+            WeatherKind::Rain => 0.3,
+            WeatherKind::Snow => 0.1,
+        }
+    }
+}
+
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Weather {
+    pub season: Season,
+    pub kind: WeatherKind,
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Weather {
+            season: Season::Spring,
+            kind: WeatherKind::Clear,
+        }
+    }
+}
+
+impl Weather {
+    pub fn roll(&mut self, season: Season, rng: &mut SimRng) {
+        let odds = season.weather_odds();
+        let mut pick = rng.below(odds.iter().sum());
+        let kinds = [WeatherKind::Clear, WeatherKind::Rain, WeatherKind::Snow];
+
+        self.season = season;
+        for (kind, weight) in kinds.into_iter().zip(odds) {
+            if pick < weight {
+                self.kind = kind;
+                return;
+            }
+            pick -= weight;
+        }
+    }
+
+    pub fn growth(&self) -> f32 {
+        self.season.growth() * self.kind.growth()
+    }
+
+    /// Clear summer days, when a fire can start on its own.
+    pub fn is_dry(&self) -> bool {
+        self.season == Season::Summer && self.kind == WeatherKind::Clear
+    }
+}
+
+/// Gives one random tree a chance to seed an empty neighbouring tile. Returns
+/// the tile that grew, if any.
+pub fn grow_trees(
+    map: &mut WorldMap,
+    world_trees: &mut WorldTrees,
+    rng: &mut SimRng,
+    chance: f32,
+) -> Option<IVec2> {
+    if !rng.chance(chance) {
+        return None;
+    }
+
+    // sample a tile rather than the tree set so the cost doesn't grow with the forest
+    let at = IVec2::new(
+        rng.below(map.width as u32) as i32,
+        rng.below(map.height as u32) as i32,
+    );
+    if world::get(map, at.x, at.y) != Tile::Tree {
+        return None;
+    }
+
+    let to = at + NEIGHBOURS[rng.below(4) as usize];
+    // ash is as good a place to take root as bare ground
+    if !world::in_bounds(map, to.x, to.y)
+        || !matches!(world::get(map, to.x, to.y), Tile::Ground | Tile::Burnt)
+    {
+        return None;
+    }
+
+    world::set(map, to.x, to.y, Tile::Tree);
+    world_trees.0.insert(to);
+    Some(to)
+}
+
+/// A falling raindrop or snowflake.
+#[derive(Component)]
+pub struct Precipitation {
+    pub speed: f32,
+    pub drift: f32,
+}
+
+pub fn spawn_precipitation(commands: &mut Commands, config: &GameConfig) {
+    let size = Vec2::new(config.map_w as f32, config.map_h as f32) * config.tile_size;
+    let count = ((config.map_w * config.map_h) as usize / 8).clamp(1, MAX_PARTICLES);
+
+    // purely cosmetic, so it gets its own generator and leaves the sim's alone
+    let mut rng = SimRng::new(0x5eed);
+    for _ in 0..count {
+        let pos = (Vec2::new(rng.next_f32(), rng.next_f32()) - 0.5) * size;
+        commands.spawn((
+            Sprite::default(),
+            Transform::from_xyz(pos.x, pos.y, 4.0),
+            Visibility::Hidden,
+            Precipitation {
+                speed: 0.8 + rng.next_f32() * 0.4,
+                drift: rng.next_f32() - 0.5,
+            },
+        ));
+    }
+}
+
+pub fn update_precipitation(
+    time: Res<Time>,
+    config: Res<GameConfig>,
+    weather: Option<Res<Weather>>,
+    mut q: Query<(&Precipitation, &mut Transform, &mut Sprite, &mut Visibility)>,
+) {
+    let kind = weather.map_or(WeatherKind::Clear, |w| w.kind);
+    let (color, size, fall) = match kind {
+        WeatherKind::Clear => {
+            for (_, _, _, mut visibility) in &mut q {
+                visibility.set_if_neq(Visibility::Hidden);
+            }
+            return;
+        }
+        WeatherKind::Rain => (RAIN_COLOR, Vec2::new(1.5, 8.0), 40.0),
+        WeatherKind::Snow => (SNOW_COLOR, Vec2::splat(3.0), 6.0),
+    };
+
+    let half = Vec2::new(config.map_w as f32, config.map_h as f32) * config.tile_size * 0.5;
+    let dt = time.delta_secs();
+    for (drop, mut transform, mut sprite, mut visibility) in &mut q {
+        visibility.set_if_neq(Visibility::Inherited);
+        sprite.color = color;
+        sprite.custom_size = Some(size);
+
+        let t = &mut transform.translation;
+        t.y -= drop.speed * fall * config.tile_size * dt;
+        if kind == WeatherKind::Snow {
+            t.x += drop.drift * config.tile_size * dt;
+        }
+
+        // wrap around so the same particles keep the whole map covered
+        if t.y < -half.y {
+            t.y += half.y * 2.0;
+        }
+        if t.x < -half.x {
+            t.x += half.x * 2.0;
+        } else if t.x > half.x {
+            t.x -= half.x * 2.0;
+        }
+    }
+}
@@ -1,135 +1,309 @@
-use bevy::{platform::collections::HashSet, prelude::*};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
 
-use crate::config::*;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+use crate::clock;
+use crate::config::GameConfig;
+use crate::pawn::Inventory;
+use crate::scenario::Scenario;
+use crate::sim::SimSet;
+use crate::tilemap;
+use crate::weather;
+
+/// Width and height of a render chunk, in tiles.
+pub const CHUNK_SIZE: i32 = 32;
+
+const NEIGHBOURS: [IVec2; 4] = [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y];
+
+/// Move costs come in quarters, so routes are costed in whole quarter steps.
+const COST_SCALE: f32 = 4.0;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Tile {
     Ground,
     Tree,
     Stockpile,
+    Burning,
+    Burnt,
+    ShallowWater,
+    DeepWater,
+    Rock,
+    Sand,
+    Mud,
+    Workbench,
+    Bed,
 }
 
-#[derive(Component)]
-pub struct TileSprite;
+impl Tile {
+    /// Whether pawns and creatures can stand on the tile at all.
+    pub fn is_walkable(self) -> bool {
+        !matches!(self, Tile::DeepWater | Tile::Rock)
+    }
 
-#[derive(Resource)]
-pub struct TileEntities {
-    pub entities: Vec<Entity>,
+    /// Effort it takes to step onto the tile, where open ground costs 1.
+    pub fn move_cost(self) -> f32 {
+        match self {
+            Tile::ShallowWater => 2.0,
+            Tile::Mud => 1.75,
+            Tile::Sand => 1.25,
+            _ => 1.0,
+        }
+    }
 }
 
 #[derive(Resource)]
 pub struct WorldTrees(pub HashSet<IVec2>);
 
-#[derive(Resource)]
+/// Rock tiles the player has marked for pawns to dig out.
+#[derive(Resource, Default)]
+pub struct MineDesignations(pub HashSet<IVec2>);
+
+/// Items lying on the ground waiting to be hauled, by tile.
+#[derive(Resource, Default)]
+pub struct DroppedItems(pub HashMap<IVec2, Inventory>);
+
+impl WorldTrees {
+    pub fn from_map(map: &WorldMap) -> Self {
+        let trees = (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| IVec2::new(x, y)))
+            .filter(|p| get(map, p.x, p.y) == Tile::Tree)
+            .collect();
+        WorldTrees(trees)
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WorldSet {
+    Build,
+    Render,
+}
+
+pub struct WorldPlugin;
+
+impl Plugin for WorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameConfig>()
+            .configure_sets(Update, WorldSet::Render.after(SimSet::PostTick))
+            .add_systems(Startup, setup_world.in_set(WorldSet::Build))
+            .add_systems(
+                Update,
+                (
+                    tilemap::update_dirty_chunks,
+                    clock::update_daylight_overlay,
+                    weather::update_precipitation,
+                )
+                    .in_set(WorldSet::Render),
+            );
+    }
+}
+
+fn setup_world(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    config: Res<GameConfig>,
+    scenario: Option<Res<Scenario>>,
+) {
+    let mut world = match scenario {
+        Some(scenario) => scenario.build_map(&config),
+        None => build_world(&config),
+    };
+    commands.insert_resource(WorldTrees::from_map(&world));
+    tilemap::spawn_tile_chunks(&mut commands, &mut images, &mut world, &config);
+    clock::spawn_daylight_overlay(&mut commands, &config);
+    weather::spawn_precipitation(&mut commands, &config);
+    commands.insert_resource(world);
+}
+
+#[derive(Resource, Clone)]
 pub struct WorldMap {
+    pub width: i32,
+    pub height: i32,
     pub tiles: Vec<Tile>,
+    /// Chunks whose tiles changed since they were last drawn.
+    pub dirty_chunks: HashSet<IVec2>,
+    /// Bumped whenever a tile changes whether it can be walked on, so routes
+    /// worked out before know to look again.
+    pub revision: u64,
+    /// Which walkable area each tile belongs to, as of `regions_revision`.
+    regions: Vec<u32>,
+    regions_revision: Option<u64>,
+}
+
+impl WorldMap {
+    pub fn new(width: i32, height: i32) -> Self {
+        WorldMap {
+            width,
+            height,
+            tiles: vec![Tile::Ground; (width * height) as usize],
+            dirty_chunks: HashSet::new(),
+            revision: 0,
+            regions: Vec::new(),
+            regions_revision: None,
+        }
+    }
+
+    pub fn chunk_count(&self) -> IVec2 {
+        IVec2::new(
+            (self.width + CHUNK_SIZE - 1) / CHUNK_SIZE,
+            (self.height + CHUNK_SIZE - 1) / CHUNK_SIZE,
+        )
+    }
 }
 
-pub fn build_world() -> WorldMap {
-    let tiles = vec![Tile::Ground; (MAP_W * MAP_H) as usize];
-    let mut world = WorldMap { tiles };
+pub fn build_world(config: &GameConfig) -> WorldMap {
+    let (w, h) = (config.map_w, config.map_h);
+    let mut world = WorldMap::new(w, h);
+
+    // forest layout is authored for a 64x64 map and scaled to the configured size
+    let sx = |v: i32| v * w / 64;
+    let sy = |v: i32| v * h / 64;
 
-    for y in 0..48 {
-        for x in 0..18 {
+    for y in 0..sy(48) {
+        for x in 0..sx(18) {
             set(&mut world, x, y, Tile::Tree);
         }
     }
 
-    for y in 0..18 {
-        for x in 0..58 {
+    for y in 0..sy(18) {
+        for x in 0..sx(58) {
             set(&mut world, x, y, Tile::Tree);
         }
     }
 
-    for y in 46..64 {
-        for x in 0..58 {
+    for y in sy(46)..h {
+        for x in 0..sx(58) {
             set(&mut world, x, y, Tile::Tree);
         }
     }
 
-    set(&mut world, STOCKPILE_X, STOCKPILE_Y, Tile::Stockpile);
+    // a lake ringed by shallows and a beach, east of the stockpile
+    let lake = IVec2::new(sx(46), sy(28));
+    let deep = sx(3) as f32;
+    for y in 0..h {
+        for x in 0..w {
+            let dist = (IVec2::new(x, y) - lake).as_vec2().length();
+            let tile = if dist <= deep {
+                Tile::DeepWater
+            } else if dist <= deep + 2.0 {
+                Tile::ShallowWater
+            } else if dist <= deep + 3.0 {
+                Tile::Sand
+            } else {
+                continue;
+            };
+            set(&mut world, x, y, tile);
+        }
+    }
+
+    // a rocky outcrop along the east edge, and a boggy patch by the forest
+    for y in sy(20)..sy(44) {
+        for x in sx(60)..w {
+            set(&mut world, x, y, Tile::Rock);
+        }
+    }
+    for y in sy(36)..sy(40) {
+        for x in sx(22)..sx(26) {
+            set(&mut world, x, y, Tile::Mud);
+        }
+    }
+
+    let stockpile = config.stockpile();
+    set(&mut world, stockpile.x, stockpile.y, Tile::Stockpile);
 
     world
 }
 
-pub fn spawn_world_tiles(commands: &mut Commands, world: &WorldMap) {
-    let mut tile_entities = Vec::with_capacity((MAP_W * MAP_H) as usize);
-    let mut world_trees = HashSet::with_capacity((MAP_W * MAP_H) as usize);
-
-    for y in 0..MAP_H {
-        for x in 0..MAP_W {
-            let tile = get(world, x, y);
-            if tile == Tile::Tree {
-                world_trees.insert(IVec2::new(x, y));
-            }
+/// Parses a map drawn as rows of `.` (ground), `T` (tree), `S` (stockpile),
+/// `-` (shallow water), `~` (deep water), `#` (rock), `:` (sand) and `%` (mud).
+/// The first row is the top of the map.
+pub fn parse_ascii_map(ascii: &str) -> Result<WorldMap, String> {
+    let rows: Vec<&str> = ascii
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect();
 
-            let color = tile_color(tile);
+    let height = rows.len() as i32;
+    let width = rows.first().map_or(0, |row| row.chars().count()) as i32;
+    if width == 0 {
+        return Err("map is empty".to_string());
+    }
 
-            let world_pos = grid_to_world(x, y);
+    let mut world = WorldMap::new(width, height);
 
-            let tile_entity = commands
-                .spawn((
-                    Sprite {
-                        color,
-                        custom_size: Some(Vec2::splat(TILE_SIZE - TILE_GAP)),
-                        ..default()
-                    },
-                    Transform::from_translation(world_pos),
-                    TileSprite,
-                ))
-                .id();
+    for (row, line) in rows.iter().enumerate() {
+        if line.chars().count() as i32 != width {
+            return Err(format!("row {row} is not {width} tiles wide"));
+        }
 
-            tile_entities.push(tile_entity);
+        let y = height - 1 - row as i32;
+        for (x, c) in line.chars().enumerate() {
+            let tile = match c {
+                '.' => Tile::Ground,
+                'T' => Tile::Tree,
+                'S' => Tile::Stockpile,
+                '-' => Tile::ShallowWater,
+                '~' => Tile::DeepWater,
+                '#' => Tile::Rock,
+                ':' => Tile::Sand,
+                '%' => Tile::Mud,
+                _ => return Err(format!("unknown tile '{c}' at row {row}, column {x}")),
+            };
+            set(&mut world, x as i32, y, tile);
         }
     }
 
-    commands.insert_resource(TileEntities {
-        entities: tile_entities,
-    });
-
-    commands.insert_resource(WorldTrees(world_trees));
+    Ok(world)
 }
 
-pub fn grid_to_world(x: i32, y: i32) -> Vec3 {
-    let origin_x = -(MAP_W as f32) * TILE_SIZE * 0.5 + TILE_SIZE * 0.5;
-    let origin_y = -(MAP_H as f32) * TILE_SIZE * 0.5 + TILE_SIZE * 0.5;
+pub fn grid_to_world(config: &GameConfig, x: i32, y: i32) -> Vec3 {
+    let tile_size = config.tile_size;
+    let origin_x = -(config.map_w as f32) * tile_size * 0.5 + tile_size * 0.5;
+    let origin_y = -(config.map_h as f32) * tile_size * 0.5 + tile_size * 0.5;
 
     Vec3::new(
-        origin_x + x as f32 * TILE_SIZE,
-        origin_y + y as f32 * TILE_SIZE,
+        origin_x + x as f32 * tile_size,
+        origin_y + y as f32 * tile_size,
         0.0,
     )
 }
 
-fn idx(x: i32, y: i32) -> usize {
-    (y * MAP_W + x) as usize
+pub fn world_to_grid(config: &GameConfig, pos: Vec2) -> Option<IVec2> {
+    let tile_size = config.tile_size;
+    let origin_x = -(config.map_w as f32) * tile_size * 0.5;
+    let origin_y = -(config.map_h as f32) * tile_size * 0.5;
+
+    let x = ((pos.x - origin_x) / tile_size).floor() as i32;
+    let y = ((pos.y - origin_y) / tile_size).floor() as i32;
+
+    if (0..config.map_w).contains(&x) && (0..config.map_h).contains(&y) {
+        Some(IVec2::new(x, y))
+    } else {
+        None
+    }
 }
 
-pub fn get(map: &WorldMap, x: i32, y: i32) -> Tile {
-    map.tiles[idx(x, y)]
+pub fn in_bounds(map: &WorldMap, x: i32, y: i32) -> bool {
+    (0..map.width).contains(&x) && (0..map.height).contains(&y)
 }
 
-pub fn set(map: &mut WorldMap, x: i32, y: i32, tile: Tile) {
-    map.tiles[idx(x, y)] = tile;
+fn idx(map: &WorldMap, x: i32, y: i32) -> usize {
+    (y * map.width + x) as usize
 }
 
-pub fn set_with_sprite(
-    map: &mut WorldMap,
-    tiles: &TileEntities,
-    q_tiles: &mut Query<&mut Sprite, With<TileSprite>>,
-    x: i32,
-    y: i32,
-    tile: Tile,
-) {
-    set(map, x, y, tile);
-    let e = tile_entity(tiles, x, y);
-    if let Ok(mut sprite) = q_tiles.get_mut(e) {
-        sprite.color = tile_color(tile);
-    }
+pub fn get(map: &WorldMap, x: i32, y: i32) -> Tile {
+    map.tiles[idx(map, x, y)]
 }
 
-pub fn tile_entity(tiles: &TileEntities, x: i32, y: i32) -> Entity {
-    tiles.entities[idx(x, y)]
+pub fn set(map: &mut WorldMap, x: i32, y: i32, tile: Tile) {
+    let i = idx(map, x, y);
+    if map.tiles[i].is_walkable() != tile.is_walkable() {
+        map.revision += 1;
+    }
+    map.tiles[i] = tile;
+    map.dirty_chunks.insert(IVec2::new(x, y) / CHUNK_SIZE);
 }
 
 pub fn tile_color(tile: Tile) -> Color {
@@ -137,5 +311,184 @@ pub fn tile_color(tile: Tile) -> Color {
         Tile::Ground => Color::srgb(0.15, 0.15, 0.15),
         Tile::Tree => Color::srgb(0.10, 0.35, 0.12),
         Tile::Stockpile => Color::srgb(0.55, 0.42, 0.15),
+        Tile::Burning => Color::srgb(0.85, 0.35, 0.05),
+        Tile::Burnt => Color::srgb(0.07, 0.06, 0.05),
+        Tile::ShallowWater => Color::srgb(0.20, 0.42, 0.60),
+        Tile::DeepWater => Color::srgb(0.07, 0.18, 0.42),
+        Tile::Rock => Color::srgb(0.45, 0.45, 0.48),
+        Tile::Sand => Color::srgb(0.68, 0.62, 0.42),
+        Tile::Mud => Color::srgb(0.30, 0.22, 0.12),
+        Tile::Workbench => Color::srgb(0.62, 0.36, 0.20),
+        Tile::Bed => Color::srgb(0.52, 0.22, 0.30),
+    }
+}
+
+pub fn is_walkable(map: &WorldMap, at: IVec2) -> bool {
+    in_bounds(map, at.x, at.y) && get(map, at.x, at.y).is_walkable()
+}
+
+/// The tile to step onto next on a shortest walk from `from` to `to`. `None`
+/// when already there or there's no way through. Works the whole route out,
+/// so anything that walks every tick should keep a [`Path`] instead.
+pub fn next_step(map: &WorldMap, from: IVec2, to: IVec2) -> Option<IVec2> {
+    find_path(map, from, to)?.last().copied()
+}
+
+/// A route worked out once and followed a step at a time. It's worked out
+/// afresh when the target moves, when the map's walkable tiles change, or when
+/// the walker finds itself off the route.
+#[derive(Debug, Clone, Default)]
+pub struct Path {
+    /// Target, revision and start the route was worked out for.
+    key: Option<(IVec2, u64, IVec2)>,
+    /// Tiles still to walk, the next one last.
+    steps: Vec<IVec2>,
+}
+
+impl Path {
+    pub fn next_step(&mut self, map: &WorldMap, from: IVec2, to: IVec2) -> Option<IVec2> {
+        if from == to {
+            return None;
+        }
+        if self.steps.last() == Some(&from) {
+            self.steps.pop();
+        }
+
+        let fresh = match (self.key, self.steps.last()) {
+            (Some((target, revision, _)), Some(&next)) => {
+                target == to && revision == map.revision && (next - from).abs().element_sum() == 1
+            }
+            // with no route at all, only worth another look from somewhere else
+            (Some((target, revision, start)), None) => {
+                target == to && revision == map.revision && start == from
+            }
+            (None, _) => false,
+        };
+        if !fresh {
+            self.key = Some((to, map.revision, from));
+            self.steps = find_path(map, from, to).unwrap_or_default();
+        }
+        self.steps.last().copied()
+    }
+}
+
+/// The cheapest walkable route from `from` to `to`, the first step last, with
+/// each step costing the [`Tile::move_cost`] of the tile stepped onto. Only
+/// the target itself may be unwalkable, e.g. a rock face being dug out.
+///
+/// Works out what it costs to get to the target from every tile, cheapest
+/// first and stopping once `from` is settled, then walks downhill from
+/// `from`. Where there's a choice it heads straight for the target, x before
+/// y, so on open ground the route is an L rather than a staircase.
+fn find_path(map: &WorldMap, from: IVec2, to: IVec2) -> Option<Vec<IVec2>> {
+    if !in_bounds(map, from.x, from.y) || !in_bounds(map, to.x, to.y) {
+        return None;
+    }
+
+    let step_cost = |at: IVec2| (get(map, at.x, at.y).move_cost() * COST_SCALE).round() as u32;
+    let mut cost = vec![u32::MAX; map.tiles.len()];
+    let mut queue = BinaryHeap::from([Reverse((0, to.x, to.y))]);
+    cost[idx(map, to.x, to.y)] = 0;
+
+    while let Some(Reverse((c, x, y))) = queue.pop() {
+        let at = IVec2::new(x, y);
+        if at == from {
+            break;
+        }
+        if c > cost[idx(map, x, y)] {
+            continue;
+        }
+        // walking the other way, the step from a neighbour lands on `at`
+        let through = c + step_cost(at);
+        for offset in NEIGHBOURS {
+            let next = at + offset;
+            if !is_walkable(map, next) || through >= cost[idx(map, next.x, next.y)] {
+                continue;
+            }
+            cost[idx(map, next.x, next.y)] = through;
+            queue.push(Reverse((through, next.x, next.y)));
+        }
+    }
+
+    let mut left = *cost.get(idx(map, from.x, from.y))?;
+    if left == u32::MAX {
+        return None;
+    }
+
+    let mut steps = Vec::new();
+    let mut at = from;
+    while at != to {
+        let d = to - at;
+        let preferred = [
+            IVec2::new(d.x.signum(), 0),
+            IVec2::new(0, d.y.signum()),
+        ];
+        at = preferred
+            .into_iter()
+            .chain(NEIGHBOURS)
+            .map(|offset| at + offset)
+            .find(|&next| {
+                next != at
+                    && in_bounds(map, next.x, next.y)
+                    && cost[idx(map, next.x, next.y)].checked_add(step_cost(next)) == Some(left)
+            })?;
+        left = cost[idx(map, at.x, at.y)];
+        steps.push(at);
+    }
+    steps.reverse();
+    Some(steps)
+}
+
+/// Labels every walkable area so reachability is a lookup. Runs whenever a
+/// tile has changed whether it can be walked on since the last time.
+pub fn refresh_regions(map: &mut WorldMap) {
+    if map.regions_revision == Some(map.revision) {
+        return;
+    }
+
+    let mut regions = vec![u32::MAX; map.tiles.len()];
+    let mut next_region = 0;
+    for start in 0..map.tiles.len() {
+        if regions[start] != u32::MAX || !map.tiles[start].is_walkable() {
+            continue;
+        }
+        regions[start] = next_region;
+        let mut queue = VecDeque::from([IVec2::new(
+            start as i32 % map.width,
+            start as i32 / map.width,
+        )]);
+        while let Some(at) = queue.pop_front() {
+            for offset in NEIGHBOURS {
+                let next = at + offset;
+                if is_walkable(map, next) && regions[idx(map, next.x, next.y)] == u32::MAX {
+                    regions[idx(map, next.x, next.y)] = next_region;
+                    queue.push_back(next);
+                }
+            }
+        }
+        next_region += 1;
+    }
+
+    map.regions = regions;
+    map.regions_revision = Some(map.revision);
+}
+
+/// Whether a walker at `from` can get onto `to`, or right next to it when
+/// `beside` is set. Assumes the regions are up to date.
+pub fn can_reach(map: &WorldMap, from: IVec2, to: IVec2, beside: bool) -> bool {
+    let region = |at: IVec2| {
+        is_walkable(map, at)
+            .then(|| map.regions.get(idx(map, at.x, at.y)).copied())
+            .flatten()
+    };
+    let Some(home) = region(from) else {
+        return false;
+    };
+
+    let arrive = |at: IVec2| region(at) == Some(home);
+    if beside {
+        arrive(to) || NEIGHBOURS.iter().any(|&offset| arrive(to + offset))
+    } else {
+        arrive(to)
     }
 }
@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+
+use tiny_colony::clock::{ClockTime, daylight_tint};
+
+#[test]
+fn ticks_map_to_days_and_hours() {
+    // ten ticks an hour, starting at 06:00 on day 1
+    assert_eq!(
+        ClockTime::at(0, 10),
+        ClockTime {
+            day: 1,
+            hour: 6,
+            minute: 0
+        }
+    );
+    assert_eq!(ClockTime::at(15, 10).describe(), "Day 1, 07:30");
+    assert_eq!(ClockTime::at(180, 10).describe(), "Day 2, 00:00");
+}
+
+#[test]
+fn nights_are_dark_and_days_are_clear() {
+    assert!(ClockTime::at(180, 10).is_night());
+    assert!(!ClockTime::at(60, 10).is_night());
+
+    assert_eq!(daylight_tint(12.0).alpha(), 0.0);
+    assert!(daylight_tint(1.0).alpha() > 0.0);
+    assert!(daylight_tint(6.0).alpha() < daylight_tint(1.0).alpha());
+}
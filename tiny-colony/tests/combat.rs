@@ -0,0 +1,114 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::combat::Stance;
+use tiny_colony::config::GameConfig;
+use tiny_colony::creatures::{Creature, CreatureKind};
+use tiny_colony::events::EventLog;
+use tiny_colony::health::Health;
+
+fn creatures(h: &mut Harness) -> Vec<(CreatureKind, IVec2)> {
+    let world = h.app.world_mut();
+    world
+        .query::<&Creature>()
+        .iter(world)
+        .map(|c| (c.kind, c.pos()))
+        .collect()
+}
+
+fn logged(h: &Harness, text: &str) -> bool {
+    let log = h.app.world().resource::<EventLog>();
+    log.entries.iter().any(|entry| entry.contains(text))
+}
+
+#[test]
+fn healthy_pawn_fights_off_a_wolf() {
+    let mut h = Harness::from_ascii("S......");
+    let pawn = h.spawn_pawn(2, 0);
+    h.spawn_creature(CreatureKind::Wolf, 3, 0);
+
+    // both swing every five ticks; the pawn hits first and the wolf falls on the fifth blow
+    h.run_ticks(20);
+    let entity = h.pawn_entity(pawn);
+    assert!(matches!(
+        h.app.world().get::<Stance>(entity),
+        Some(Stance::Fight(_))
+    ));
+
+    h.run_ticks(2);
+    assert!(creatures(&mut h).is_empty());
+    assert_eq!(h.app.world().get::<Health>(entity).unwrap().current, 68);
+    assert_eq!(h.app.world().get::<Stance>(entity), Some(&Stance::Work));
+    assert!(logged(&h, "A wolf was killed"));
+}
+
+#[test]
+fn wounded_pawn_runs_away() {
+    let mut h = Harness::from_ascii("S.........");
+    let pawn = h.spawn_pawn(5, 0);
+    let entity = h.pawn_entity(pawn);
+    h.app.world_mut().get_mut::<Health>(entity).unwrap().current = 30;
+    h.spawn_creature(CreatureKind::Wolf, 8, 0);
+
+    h.run_ticks(1);
+    assert_eq!(h.position(pawn), IVec2::new(4, 0));
+    assert!(matches!(
+        h.app.world().get::<Stance>(entity),
+        Some(Stance::Flee(_))
+    ));
+    assert_eq!(
+        creatures(&mut h),
+        vec![(CreatureKind::Wolf, IVec2::new(7, 0))]
+    );
+}
+
+#[test]
+fn killed_pawns_give_up_their_reservation() {
+    let mut h = Harness::from_ascii("S.....T");
+    let pawn = h.spawn_pawn(3, 0);
+    h.run_ticks(1);
+    assert_eq!(h.reservations().by_pawn.len(), 1);
+
+    let entity = h.pawn_entity(pawn);
+    h.app.world_mut().get_mut::<Health>(entity).unwrap().current = 5;
+    h.spawn_creature(CreatureKind::Wolf, 4, 0);
+
+    // the pawn runs for the stockpile corner but the wolf corners it
+    h.run_ticks(6);
+    assert!(h.app.world().get_entity(entity).is_err());
+    assert!(h.reservations().reserved_tiles.is_empty());
+    assert!(h.reservations().by_pawn.is_empty());
+    assert!(logged(&h, "Pawn 0 died in combat"));
+}
+
+#[test]
+fn raiders_arrive_on_schedule_at_the_map_edge() {
+    let mut h = Harness::from_ascii(
+        "
+        ..........
+        ..........
+        ....S.....
+        ..........
+        ..........
+        ",
+    );
+    {
+        let mut config = h.app.world_mut().resource_mut::<GameConfig>();
+        config.raid_every_hours = 1;
+        config.raid_size = 3;
+    }
+
+    h.run_ticks(9);
+    assert!(creatures(&mut h).is_empty());
+
+    h.run_ticks(1);
+    let raiders = creatures(&mut h);
+    assert!(!raiders.is_empty());
+    for (kind, at) in raiders {
+        assert_eq!(kind, CreatureKind::Raider);
+        assert!(at.x == 0 || at.y == 0 || at.x == 9 || at.y == 4);
+    }
+    assert!(logged(&h, "raiders arrived"));
+}
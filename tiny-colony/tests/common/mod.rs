@@ -0,0 +1,207 @@
+#![allow(dead_code)]
+
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+
+use tiny_colony::SimPlugin;
+use tiny_colony::colony::{Colony, Item};
+use tiny_colony::config::GameConfig;
+use tiny_colony::crafting::{BenchKind, Bill, Workbench, Workbenches};
+use tiny_colony::creatures::{self, CreatureKind, Wildlife};
+use tiny_colony::fire::{self, Fires};
+use tiny_colony::pawn::{self, Inventory, NextPawnId, Pawn, Task};
+use tiny_colony::sim::{Reservations, Sim};
+use tiny_colony::world::{self, MineDesignations, Tile, WorldMap, WorldTrees};
+
+/// A headless app running only the sim systems on a hand-drawn map.
+pub struct Harness {
+    pub app: App,
+}
+
+impl Harness {
+    pub fn from_ascii(ascii: &str) -> Self {
+        let map = world::parse_ascii_map(ascii).expect("invalid test map");
+
+        let stockpile = (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| IVec2::new(x, y)))
+            .find(|p| world::get(&map, p.x, p.y) == Tile::Stockpile)
+            .unwrap_or(IVec2::ZERO);
+
+        let config = GameConfig {
+            map_w: map.width,
+            map_h: map.height,
+            stockpile_x: Some(stockpile.x),
+            stockpile_y: Some(stockpile.y),
+            pawn_count: 0,
+            // keep the world still so tests can count ticks exactly
+            weather_hours: 0,
+            tree_growth_chance: 0.0,
+            fire_chance: 0.0,
+            animal_count: 0,
+            raid_every_hours: 0,
+            hunger_ticks: 0,
+            accident_chance: 0.0,
+            wanderer_every_hours: 0,
+            caravan_every_hours: 0,
+            ..default()
+        };
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(config.clone())
+            .add_plugins(SimPlugin);
+
+        let world = app.world_mut();
+        world.insert_resource(WorldTrees::from_map(&map));
+        world.insert_resource(map);
+
+        // run Startup, then stop the real-time clock from driving ticks
+        app.update();
+        app.world_mut().resource_mut::<Sim>().paused = true;
+
+        Harness { app }
+    }
+
+    pub fn spawn_pawn(&mut self, x: i32, y: i32) -> u32 {
+        let id = self.app.world().resource::<NextPawnId>().0;
+        let spawned = self.spawn_pawns(1, x, y);
+        assert_eq!(spawned, 1, "could not place pawn at ({x},{y})");
+        id
+    }
+
+    /// Spreads up to `count` pawns over the free tiles around `(x, y)`.
+    pub fn spawn_pawns(&mut self, count: usize, x: i32, y: i32) -> usize {
+        let world = self.app.world_mut();
+        let spawned = world.resource_scope(|world, map: Mut<WorldMap>| {
+            world.resource_scope(|world, mut ids: Mut<NextPawnId>| {
+                pawn::spawn_pawns_at(
+                    &mut world.commands(),
+                    &map,
+                    IVec2::new(x, y),
+                    count,
+                    &mut ids,
+                    &mut HashSet::new(),
+                )
+            })
+        });
+        world.flush();
+        spawned
+    }
+
+    /// Queues `ticks` and runs frames until they have all gone through.
+    pub fn run_ticks(&mut self, ticks: u32) {
+        let before = self.sim_ticks();
+        self.app.world_mut().resource_mut::<Sim>().pending_ticks += ticks;
+        while self.app.world().resource::<Sim>().pending_ticks > 0 {
+            self.app.update();
+        }
+        assert_eq!(self.sim_ticks(), before + ticks as u64);
+    }
+
+    pub fn sim_ticks(&self) -> u64 {
+        self.app.world().resource::<Sim>().ticks
+    }
+
+    pub fn colony(&self) -> &Colony {
+        self.app.world().resource::<Colony>()
+    }
+
+    pub fn reservations(&self) -> &Reservations {
+        self.app.world().resource::<Reservations>()
+    }
+
+    pub fn tile(&self, x: i32, y: i32) -> Tile {
+        world::get(self.app.world().resource::<WorldMap>(), x, y)
+    }
+
+    pub fn set_tile(&mut self, x: i32, y: i32, tile: Tile) {
+        let mut map = self.app.world_mut().resource_mut::<WorldMap>();
+        world::set(&mut map, x, y, tile);
+    }
+
+    pub fn stock(&mut self, item: Item, amount: u32) {
+        *self
+            .app
+            .world_mut()
+            .resource_mut::<Colony>()
+            .amount_mut(item) = amount;
+    }
+
+    /// Puts a bench down for free, skipping the building cost.
+    pub fn place_bench(&mut self, kind: BenchKind, x: i32, y: i32, bill: Bill) {
+        self.set_tile(x, y, Tile::Workbench);
+        self.app.world_mut().resource_mut::<Workbenches>().0.insert(
+            IVec2::new(x, y),
+            Workbench {
+                kind,
+                bill: Some(bill),
+            },
+        );
+    }
+
+    pub fn bench(&self, x: i32, y: i32) -> Workbench {
+        self.app.world().resource::<Workbenches>().0[&IVec2::new(x, y)]
+    }
+
+    pub fn designate_mine(&mut self, x: i32, y: i32) {
+        self.app
+            .world_mut()
+            .resource_mut::<MineDesignations>()
+            .0
+            .insert(IVec2::new(x, y));
+    }
+
+    pub fn ignite(&mut self, x: i32, y: i32) {
+        let world = self.app.world_mut();
+        world.resource_scope(|world, mut map: Mut<WorldMap>| {
+            world.resource_scope(|world, mut trees: Mut<WorldTrees>| {
+                let mut fires = world.resource_mut::<Fires>();
+                assert!(fire::ignite(
+                    &mut map,
+                    &mut trees,
+                    &mut fires,
+                    IVec2::new(x, y)
+                ));
+            });
+        });
+    }
+
+    pub fn spawn_creature(&mut self, kind: CreatureKind, x: i32, y: i32) -> Entity {
+        let world = self.app.world_mut();
+        let entity = world.resource_scope(|world, mut wildlife: Mut<Wildlife>| {
+            creatures::spawn_creature(&mut world.commands(), &mut wildlife, kind, IVec2::new(x, y))
+        });
+        world.flush();
+        entity
+    }
+
+    pub fn fires(&self) -> &Fires {
+        self.app.world().resource::<Fires>()
+    }
+
+    pub fn pawn_entity(&mut self, id: u32) -> Entity {
+        let world = self.app.world_mut();
+        world
+            .query::<(Entity, &Pawn)>()
+            .iter(world)
+            .find(|(_, pawn)| pawn.id == id)
+            .map(|(entity, _)| entity)
+            .unwrap_or_else(|| panic!("no pawn with id {id}"))
+    }
+
+    pub fn task(&mut self, id: u32) -> Task {
+        let entity = self.pawn_entity(id);
+        *self.app.world().get::<Task>(entity).unwrap()
+    }
+
+    pub fn position(&mut self, id: u32) -> IVec2 {
+        let entity = self.pawn_entity(id);
+        let pawn = self.app.world().get::<Pawn>(entity).unwrap();
+        IVec2::new(pawn.x, pawn.y)
+    }
+
+    pub fn inventory(&mut self, id: u32) -> Inventory {
+        let entity = self.pawn_entity(id);
+        *self.app.world().get::<Inventory>(entity).unwrap()
+    }
+}
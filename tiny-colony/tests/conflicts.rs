@@ -0,0 +1,66 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::pawn::Task;
+
+#[test]
+fn lowest_pawn_id_wins_a_contested_tree() {
+    let mut h = Harness::from_ascii("S.T..");
+    let a = h.spawn_pawn(1, 0);
+    let b = h.spawn_pawn(3, 0);
+
+    h.run_ticks(1);
+
+    assert_eq!(h.task(a), Task::GoToTree(IVec2::new(2, 0)));
+    assert_eq!(h.task(b), Task::Idle);
+    assert_eq!(h.reservations().reserved_tiles.len(), 1);
+}
+
+#[test]
+fn loser_picks_the_next_free_tree_a_tick_later() {
+    let mut h = Harness::from_ascii("T..T.S");
+    let a = h.spawn_pawn(2, 0);
+    let b = h.spawn_pawn(4, 0);
+
+    h.run_ticks(1);
+
+    // both prefer (3,0); the lower id gets it and the other has to choose again
+    assert_eq!(h.task(a), Task::GoToTree(IVec2::new(3, 0)));
+    assert_eq!(h.task(b), Task::Idle);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(b), Task::GoToTree(IVec2::new(0, 0)));
+}
+
+fn run_forest(ticks: u32) -> (u32, Vec<Task>) {
+    let mut h = Harness::from_ascii(
+        "
+        TTTTTTTTTTTT
+        TT........TT
+        T....S.....T
+        T..........T
+        TT........TT
+        TTTTTTTTTTTT
+        ",
+    );
+
+    let ids: Vec<u32> = (0..16)
+        .map(|i| h.spawn_pawn(2 + i % 8, 2 + i / 8))
+        .collect();
+
+    h.run_ticks(ticks);
+
+    let tasks = ids.iter().map(|&id| h.task(id)).collect();
+    (h.colony().wood, tasks)
+}
+
+#[test]
+fn ticks_are_deterministic_across_runs() {
+    let first = run_forest(250);
+    let second = run_forest(250);
+
+    assert!(first.0 > 0);
+    assert_eq!(first, second);
+}
@@ -0,0 +1,143 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::colony::{Colony, Item};
+use tiny_colony::console::{self, ConsoleCommand};
+use tiny_colony::crafting::{self, BenchKind, Bill, Workbenches};
+use tiny_colony::pawn::{Inventory, Task};
+use tiny_colony::world::{self, Tile};
+
+#[test]
+fn crafter_fetches_inputs_works_the_bench_and_hauls_the_product() {
+    let mut h = Harness::from_ascii("S...");
+    let pawn = h.spawn_pawn(0, 0);
+    let bench = IVec2::new(3, 0);
+    h.place_bench(BenchKind::Sawmill, 3, 0, Bill::Until(4));
+    h.stock(Item::Wood, 5);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::FetchInputs(bench));
+    assert!(h.reservations().is_reserved(bench));
+
+    // already standing on the stockpile, so the wood comes straight out
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToBench(bench));
+    assert_eq!(h.inventory(pawn).wood, 1);
+    assert_eq!(h.colony().wood, 4);
+
+    h.run_ticks(3);
+    assert_eq!(
+        h.task(pawn),
+        Task::Craft {
+            at: bench,
+            progress: 0
+        }
+    );
+
+    h.run_ticks(15);
+    assert_eq!(h.task(pawn), Task::GoToStockpile);
+    assert_eq!(h.inventory(pawn).wood, 0);
+    assert_eq!(h.inventory(pawn).planks, 2);
+
+    h.run_ticks(4);
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert_eq!(h.colony().planks, 2);
+
+    // a second batch meets the bill and the bench goes quiet
+    h.run_ticks(100);
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert_eq!(h.colony().planks, 4);
+    assert_eq!(h.colony().wood, 3);
+    assert!(h.reservations().reserved_tiles.is_empty());
+}
+
+#[test]
+fn make_bills_count_down_and_clear() {
+    let mut h = Harness::from_ascii("S..");
+    h.spawn_pawn(0, 0);
+    h.place_bench(BenchKind::Stonecutter, 2, 0, Bill::Make(2));
+    h.stock(Item::Stone, 10);
+
+    h.run_ticks(100);
+    assert_eq!(h.colony().blocks, 2);
+    assert_eq!(h.colony().stone, 6);
+    assert_eq!(h.bench(2, 0).bill, None);
+}
+
+#[test]
+fn empty_make_bills_are_refused() {
+    assert!(console::parse_command("bill 2 0 make 0").is_err());
+    assert_eq!(
+        console::parse_command("bill 2 0 make 3"),
+        Ok(ConsoleCommand::SetBill {
+            at: IVec2::new(2, 0),
+            bill: Some(Bill::Make(3)),
+        })
+    );
+}
+
+#[test]
+fn benches_wait_for_inputs() {
+    let mut h = Harness::from_ascii("S..");
+    let pawn = h.spawn_pawn(0, 0);
+    h.place_bench(BenchKind::Stonecutter, 2, 0, Bill::Make(1));
+    h.stock(Item::Stone, 1);
+
+    h.run_ticks(5);
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert!(h.reservations().reserved_tiles.is_empty());
+
+    h.stock(Item::Stone, 2);
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::FetchInputs(IVec2::new(2, 0)));
+}
+
+#[test]
+fn placing_a_bench_costs_wood_and_stone() {
+    let mut map = world::parse_ascii_map("S.T").unwrap();
+    let mut benches = Workbenches::default();
+    let mut colony = Colony(Inventory {
+        wood: 10,
+        stone: 4,
+        ..default()
+    });
+
+    let at = IVec2::new(1, 0);
+    assert!(crafting::place(&mut map, &mut benches, &mut colony, BenchKind::Sawmill, at).is_err());
+    colony.stone = 5;
+    assert!(
+        crafting::place(
+            &mut map,
+            &mut benches,
+            &mut colony,
+            BenchKind::Sawmill,
+            IVec2::new(2, 0)
+        )
+        .is_err()
+    );
+
+    crafting::place(&mut map, &mut benches, &mut colony, BenchKind::Sawmill, at).unwrap();
+    assert_eq!((colony.wood, colony.stone), (0, 0));
+    assert_eq!(world::get(&map, 1, 0), Tile::Workbench);
+    assert_eq!(benches.0[&at].bill, None);
+}
+
+#[test]
+fn a_stonecutter_needs_steel() {
+    let mut map = world::parse_ascii_map("S..").unwrap();
+    let mut benches = Workbenches::default();
+    let mut colony = Colony(Inventory {
+        stone: 10,
+        steel: 1,
+        ..default()
+    });
+
+    let at = IVec2::new(1, 0);
+    let kind = BenchKind::Stonecutter;
+    assert!(crafting::place(&mut map, &mut benches, &mut colony, kind, at).is_err());
+    colony.steel = 2;
+    crafting::place(&mut map, &mut benches, &mut colony, kind, at).unwrap();
+    assert_eq!((colony.stone, colony.steel), (0, 0));
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::config::GameConfig;
+use tiny_colony::events::EventLog;
+use tiny_colony::fire::BURN_TICKS;
+use tiny_colony::pawn::Task;
+use tiny_colony::world::{Tile, WorldTrees};
+
+fn with_spread(ascii: &str, chance: f32) -> Harness {
+    let mut h = Harness::from_ascii(ascii);
+    h.app
+        .world_mut()
+        .resource_mut::<GameConfig>()
+        .fire_spread_chance = chance;
+    h
+}
+
+#[test]
+fn fire_spreads_through_the_forest_and_burns_out() {
+    let mut h = with_spread("S.TTT", 1.0);
+    h.ignite(2, 0);
+    assert_eq!(h.tile(2, 0), Tile::Burning);
+
+    h.run_ticks(1);
+    assert_eq!(h.tile(3, 0), Tile::Burning);
+    assert_eq!(h.tile(4, 0), Tile::Tree);
+
+    h.run_ticks(BURN_TICKS);
+    assert_eq!(h.tile(2, 0), Tile::Burnt);
+    assert_eq!(h.tile(4, 0), Tile::Burning);
+    assert!(h.app.world().resource::<WorldTrees>().0.is_empty());
+
+    h.run_ticks(2);
+    assert_eq!(h.tile(4, 0), Tile::Burnt);
+    assert!(h.fires().burning.is_empty());
+
+    // the fire took the last of the trees, so that's worth an alert
+    let log = h.app.world().resource::<EventLog>();
+    let depleted = log.entries.iter().filter(|entry| entry.contains("Forest depleted"));
+    assert_eq!(depleted.count(), 1);
+}
+
+#[test]
+fn idle_pawns_put_fires_out() {
+    let mut h = with_spread("S..TT", 0.0);
+    let pawn = h.spawn_pawn(1, 0);
+    h.ignite(4, 0);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToFire(IVec2::new(4, 0)));
+
+    // two steps brings it next to the fire
+    h.run_ticks(2);
+    assert!(matches!(h.task(pawn), Task::Extinguish { .. }));
+
+    h.run_ticks(5);
+    assert_eq!(h.tile(4, 0), Tile::Burnt);
+    assert_eq!(h.tile(3, 0), Tile::Tree);
+    assert!(h.fires().burning.is_empty());
+    assert!(h.reservations().reserved_tiles.is_empty());
+}
+
+#[test]
+fn woodcutters_give_up_trees_that_catch_fire() {
+    let mut h = with_spread("S..TT", 1.0);
+    let pawn = h.spawn_pawn(1, 0);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToTree(IVec2::new(3, 0)));
+
+    h.ignite(4, 0);
+    h.run_ticks(1);
+    assert_eq!(h.tile(3, 0), Tile::Burning);
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert!(h.reservations().by_pawn.is_empty());
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToFire(IVec2::new(3, 0)));
+}
@@ -0,0 +1,134 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::colony::Item;
+use tiny_colony::config::GameConfig;
+use tiny_colony::events::EventLog;
+use tiny_colony::health::{Corpse, Harm, Health, MEDICINE_HEAL};
+use tiny_colony::pawn::{Inventory, Pawn, Task};
+use tiny_colony::world::{DroppedItems, Tile};
+
+fn set_health(h: &mut Harness, id: u32, current: u32) {
+    let entity = h.pawn_entity(id);
+    h.app.world_mut().get_mut::<Health>(entity).unwrap().current = current;
+}
+
+fn health(h: &mut Harness, id: u32) -> u32 {
+    let entity = h.pawn_entity(id);
+    h.app.world().get::<Health>(entity).unwrap().current
+}
+
+fn logged(h: &Harness, text: &str) -> bool {
+    let log = h.app.world().resource::<EventLog>();
+    log.entries.iter().any(|entry| entry.contains(text))
+}
+
+fn run_until_felled(h: &mut Harness) -> u32 {
+    let mut ticks = 0;
+    while h.tile(2, 0) == Tile::Tree {
+        h.run_ticks(1);
+        ticks += 1;
+        assert!(ticks < 100, "tree never fell");
+    }
+    ticks
+}
+
+fn ticks_to_fell(current: u32) -> u32 {
+    let mut h = Harness::from_ascii("S.T");
+    let pawn = h.spawn_pawn(1, 0);
+    set_health(&mut h, pawn, current);
+    run_until_felled(&mut h)
+}
+
+#[test]
+fn injured_pawns_work_slower() {
+    // still well enough to keep working rather than rest
+    assert!(ticks_to_fell(60) > ticks_to_fell(100));
+}
+
+#[test]
+fn badly_hurt_pawns_rest_until_healed() {
+    let mut h = Harness::from_ascii("S...T");
+    let pawn = h.spawn_pawn(2, 0);
+    set_health(&mut h, pawn, 40);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::Rest { progress: 0 });
+
+    h.run_ticks(50);
+    assert_eq!(health(&mut h, pawn), 50);
+    assert!(matches!(h.task(pawn), Task::Rest { .. }));
+
+    set_health(&mut h, pawn, 99);
+    h.run_ticks(5);
+    assert_eq!(health(&mut h, pawn), 100);
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::Idle);
+}
+
+#[test]
+fn medicine_patches_up_a_pawn_laid_up_hurt() {
+    let mut h = Harness::from_ascii("S...T");
+    let pawn = h.spawn_pawn(2, 0);
+    h.stock(Item::Medicine, 1);
+    set_health(&mut h, pawn, 40);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::Rest { progress: 0 });
+    assert_eq!(health(&mut h, pawn), 40 + MEDICINE_HEAL);
+    assert_eq!(h.colony().medicine, 0);
+    assert!(logged(&h, "given medicine"));
+}
+
+#[test]
+fn felling_can_injure_the_woodcutter() {
+    let mut h = Harness::from_ascii("S.T");
+    h.app
+        .world_mut()
+        .resource_mut::<GameConfig>()
+        .accident_chance = 1.0;
+    let pawn = h.spawn_pawn(1, 0);
+
+    run_until_felled(&mut h);
+    assert_eq!(health(&mut h, pawn), 80);
+    assert!(logged(&h, "Pawn 0 was hurt in a work accident"));
+}
+
+#[test]
+fn starving_pawn_dies_and_its_load_is_hauled_away() {
+    let mut h = Harness::from_ascii("S....T");
+    h.app.world_mut().resource_mut::<GameConfig>().hunger_ticks = 20;
+    let dying = h.spawn_pawn(4, 0);
+    let hauler = h.spawn_pawn(1, 0);
+
+    // the dying pawn has claimed the tree and is carrying a load
+    h.run_ticks(1);
+    assert_eq!(h.task(dying), Task::GoToTree(IVec2::new(5, 0)));
+    let entity = h.pawn_entity(dying);
+    let world = h.app.world_mut();
+    world.get_mut::<Inventory>(entity).unwrap().wood = 2;
+    world.get_mut::<Pawn>(entity).unwrap().hunger = 19;
+    world.get_mut::<Health>(entity).unwrap().current = 1;
+
+    h.run_ticks(1);
+    assert!(logged(&h, "Pawn 0 died from starvation"));
+    assert!(!h.reservations().by_pawn.contains_key(&entity));
+    let world = h.app.world_mut();
+    assert!(world.get_entity(entity).is_err());
+    let corpses: Vec<Corpse> = world.query::<&Corpse>().iter(world).copied().collect();
+    assert_eq!(corpses.len(), 1);
+    assert_eq!(corpses[0].cause, Harm::Starvation);
+    assert_eq!(
+        world.resource::<DroppedItems>().0[&IVec2::new(4, 0)].wood,
+        2
+    );
+
+    h.run_ticks(1);
+    assert_eq!(h.task(hauler), Task::GoToPickup(IVec2::new(4, 0)));
+
+    h.run_ticks(10);
+    assert!(h.app.world().resource::<DroppedItems>().0.is_empty());
+    assert_eq!(h.colony().wood, 2);
+}
@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+
+use tiny_colony::input::{Action, Binding, KeyBindings};
+
+#[test]
+fn rebinding_onto_a_taken_binding_swaps_the_two() {
+    let mut bindings = KeyBindings::default();
+    let select = bindings.get(Action::Select).unwrap();
+    let console = bindings.get(Action::ToggleConsole).unwrap();
+
+    bindings.rebind(Action::ToggleConsole, select);
+    assert_eq!(bindings.get(Action::ToggleConsole), Some(select));
+    assert_eq!(bindings.get(Action::Select), Some(console));
+}
+
+#[test]
+fn rebinding_an_unbound_action_takes_the_binding_away_from_the_other() {
+    let mut bindings = KeyBindings::default();
+    bindings.0.remove(&Action::Step);
+    let pause = bindings.get(Action::TogglePause).unwrap();
+
+    bindings.rebind(Action::Step, pause);
+    assert_eq!(bindings.get(Action::Step), Some(pause));
+    assert_eq!(bindings.get(Action::TogglePause), None);
+
+    // moving on to a free key leaves everyone else where they were
+    bindings.rebind(Action::Step, Binding::Key(KeyCode::KeyP));
+    assert_eq!(
+        bindings.get(Action::Step),
+        Some(Binding::Key(KeyCode::KeyP))
+    );
+    assert_eq!(bindings.get(Action::TogglePause), None);
+}
@@ -0,0 +1,91 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::config::GameConfig;
+use tiny_colony::metrics::MetricsWriter;
+use tiny_colony::{HeadlessPlugin, SimPlugin};
+
+fn run_with_metrics(file: &str, every: u32, ticks: u32) -> Vec<String> {
+    let path = std::env::temp_dir().join(format!("tiny-colony-{}-{file}", std::process::id()));
+    let path = path.to_str().unwrap().to_string();
+
+    let mut h = Harness::from_ascii("S..T");
+    h.spawn_pawn(0, 0);
+    h.app
+        .world_mut()
+        .insert_resource(MetricsWriter::create(&path, every).unwrap());
+    h.run_ticks(ticks);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    contents.lines().map(str::to_string).collect()
+}
+
+#[test]
+fn csv_has_a_row_per_tick_with_the_delivery() {
+    let lines = run_with_metrics("metrics.csv", 1, 18);
+
+    assert_eq!(
+        lines[0],
+        "tick,wood,stone,planks,blocks,delivered,idle,go_to_tree,chop,go_to_rock,mine,fetch_inputs,go_to_bench,craft,go_to_stockpile,drop_off,go_to_fire,extinguish,go_to_pickup,rest,on_break,reserved,trees_left"
+    );
+    assert_eq!(lines.len(), 19);
+    // tick 1 reserves the tree, tick 18 drops the wood off
+    assert_eq!(lines[1], "1,0,0,0,0,0,0,1,0,0,0,0,0,0,0,0,0,0,0,0,0,1,1");
+    assert_eq!(lines[18], "18,1,0,0,0,1,1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0");
+}
+
+#[test]
+fn json_lines_respect_the_sample_interval() {
+    let lines = run_with_metrics("metrics.jsonl", 5, 18);
+
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("{\"tick\":5,"));
+    assert!(lines[2].starts_with("{\"tick\":15,"));
+    assert!(lines[2].ends_with("\"reserved\":0,\"trees_left\":0}"));
+}
+
+#[test]
+fn sampled_rows_count_every_delivery_since_the_last_row() {
+    let lines = run_with_metrics("sampled.csv", 5, 20);
+
+    // the wood lands on tick 18, between the sampled ticks
+    let delivered: Vec<&str> = lines[1..]
+        .iter()
+        .map(|line| line.split(',').nth(5).unwrap())
+        .collect();
+    assert_eq!(delivered, vec!["0", "0", "0", "1"]);
+}
+
+#[test]
+fn headless_runs_write_metrics_and_quit() {
+    let path =
+        std::env::temp_dir().join(format!("tiny-colony-{}-headless.csv", std::process::id()));
+    let path = path.to_str().unwrap().to_string();
+    let config = GameConfig {
+        map_w: 24,
+        map_h: 24,
+        pawn_count: 5,
+        metrics_path: Some(path.clone()),
+        metrics_every: 10,
+        headless_ticks: Some(40),
+        ..default()
+    };
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .insert_resource(config)
+        .add_plugins((SimPlugin, HeadlessPlugin));
+    assert_eq!(app.run(), AppExit::Success);
+
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let ticks: Vec<&str> = contents
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').next().unwrap())
+        .collect();
+    assert_eq!(ticks, vec!["10", "20", "30", "40"]);
+}
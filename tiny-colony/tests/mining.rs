@@ -0,0 +1,79 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::pawn::Task;
+use tiny_colony::world::Tile;
+
+#[test]
+fn pawn_mines_rock_and_hauls_the_stone() {
+    let mut h = Harness::from_ascii("S..#");
+    let pawn = h.spawn_pawn(0, 0);
+    let rock = IVec2::new(3, 0);
+    h.designate_mine(3, 0);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToRock(rock));
+
+    // rock can't be stood on, so the pawn digs from the tile beside it
+    h.run_ticks(2);
+    assert_eq!(h.position(pawn), IVec2::new(2, 0));
+    assert_eq!(
+        h.task(pawn),
+        Task::Mine {
+            at: rock,
+            progress: 0
+        }
+    );
+
+    h.run_ticks(19);
+    assert_eq!(h.tile(3, 0), Tile::Rock);
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToStockpile);
+    assert_eq!(h.tile(3, 0), Tile::Ground);
+    assert_eq!(h.inventory(pawn).stone, 1);
+    assert!(h.reservations().reserved_tiles.is_empty());
+
+    h.run_ticks(3);
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert_eq!(h.colony().stone, 1);
+    assert_eq!(h.colony().wood, 0);
+}
+
+#[test]
+fn only_designated_rock_is_mined() {
+    let mut h = Harness::from_ascii("S.#");
+    let pawn = h.spawn_pawn(0, 0);
+
+    h.run_ticks(5);
+    assert_eq!(h.task(pawn), Task::Idle);
+
+    h.designate_mine(2, 0);
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToRock(IVec2::new(2, 0)));
+}
+
+#[test]
+fn pawns_take_the_nearer_of_trees_and_rock() {
+    let mut h = Harness::from_ascii("T...S.#");
+    let pawn = h.spawn_pawn(4, 0);
+    h.designate_mine(6, 0);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToRock(IVec2::new(6, 0)));
+}
+
+#[test]
+fn rock_walled_in_waits_for_its_neighbours() {
+    let mut h = Harness::from_ascii("S.##");
+    let pawn = h.spawn_pawn(0, 0);
+    h.designate_mine(3, 0);
+
+    h.run_ticks(5);
+    assert_eq!(h.task(pawn), Task::Idle);
+
+    h.designate_mine(2, 0);
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToRock(IVec2::new(2, 0)));
+}
@@ -0,0 +1,121 @@
+mod common;
+
+use common::Harness;
+use tiny_colony::config::GameConfig;
+use tiny_colony::health::Health;
+use tiny_colony::mood::{Mood, Relationships, Thought};
+use tiny_colony::pawn::{Pawn, Task};
+use tiny_colony::world::Tile;
+
+fn mood(h: &mut Harness, id: u32) -> Mood {
+    let entity = h.pawn_entity(id);
+    h.app.world().get::<Mood>(entity).unwrap().clone()
+}
+
+fn remember(h: &mut Harness, id: u32, thoughts: &[Thought]) {
+    let entity = h.pawn_entity(id);
+    let mut mood = h.app.world_mut().get_mut::<Mood>(entity).unwrap();
+    for &thought in thoughts {
+        mood.remember(thought);
+    }
+}
+
+fn relationships(h: &mut Harness, id: u32) -> Relationships {
+    let entity = h.pawn_entity(id);
+    h.app.world().get::<Relationships>(entity).unwrap().clone()
+}
+
+fn ticks_to_fell(thoughts: &[Thought]) -> u32 {
+    let mut h = Harness::from_ascii("S.T");
+    let pawn = h.spawn_pawn(1, 0);
+    remember(&mut h, pawn, thoughts);
+
+    let mut ticks = 0;
+    while h.tile(2, 0) == Tile::Tree {
+        h.run_ticks(1);
+        ticks += 1;
+        assert!(ticks < 100, "tree never fell");
+    }
+    ticks
+}
+
+#[test]
+fn unhappy_pawns_work_slower() {
+    assert!(ticks_to_fell(&[Thought::LostFriend]) > ticks_to_fell(&[]));
+}
+
+#[test]
+fn miserable_pawns_take_a_break_and_feel_better_for_it() {
+    let mut h = Harness::from_ascii("S....");
+    let pawn = h.spawn_pawn(2, 0);
+    remember(&mut h, pawn, &[Thought::LostFriend, Thought::SawDeath]);
+
+    h.run_ticks(1);
+    assert_eq!(mood(&mut h, pawn).value, 10);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::Break { progress: 0 });
+
+    h.run_ticks(29);
+    assert_eq!(h.task(pawn), Task::Break { progress: 29 });
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::Idle);
+    let mood = mood(&mut h, pawn);
+    assert_eq!(mood.value, 30);
+    assert!(mood.memories.iter().any(|m| m.thought == Thought::HadBreak));
+}
+
+#[test]
+fn neighbours_form_opinions_of_each_other() {
+    let mut h = Harness::from_ascii("S.....");
+    let a = h.spawn_pawn(2, 0);
+    let b = h.spawn_pawn(3, 0);
+    let loner = h.spawn_pawn(5, 0);
+
+    h.run_ticks(100);
+    let of_b = relationships(&mut h, a).opinion_of(b);
+    assert_ne!(of_b, 0);
+    assert_eq!(relationships(&mut h, b).opinion_of(a), of_b);
+    assert!(relationships(&mut h, loner).opinions.is_empty());
+}
+
+#[test]
+fn a_death_hits_friends_harder() {
+    let mut h = Harness::from_ascii("S.......");
+    h.app.world_mut().resource_mut::<GameConfig>().hunger_ticks = 20;
+    let dying = h.spawn_pawn(7, 0);
+    let friend = h.spawn_pawn(1, 0);
+    let stranger = h.spawn_pawn(4, 0);
+
+    let entity = h.pawn_entity(friend);
+    h.app
+        .world_mut()
+        .get_mut::<Relationships>(entity)
+        .unwrap()
+        .opinions
+        .insert(dying, 50);
+    let entity = h.pawn_entity(dying);
+    let world = h.app.world_mut();
+    world.get_mut::<Pawn>(entity).unwrap().hunger = 19;
+    world.get_mut::<Health>(entity).unwrap().current = 1;
+
+    h.run_ticks(1);
+    let thoughts = |m: Mood| m.memories.iter().map(|m| m.thought).collect::<Vec<_>>();
+    assert_eq!(thoughts(mood(&mut h, friend)), vec![Thought::LostFriend]);
+    assert_eq!(thoughts(mood(&mut h, stranger)), vec![Thought::SawDeath]);
+    assert!(relationships(&mut h, friend).opinions.is_empty());
+}
+
+#[test]
+fn only_a_few_pawns_on_one_tile_chat() {
+    let mut h = Harness::from_ascii("S.....");
+    let crowd: Vec<u32> = (0..6).map(|_| h.spawn_pawn(3, 0)).collect();
+
+    h.run_ticks(10);
+    for &pawn in &crowd[..4] {
+        assert_eq!(relationships(&mut h, pawn).opinions.len(), 3);
+    }
+    for &pawn in &crowd[4..] {
+        assert!(relationships(&mut h, pawn).opinions.is_empty());
+    }
+}
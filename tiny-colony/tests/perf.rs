@@ -0,0 +1,121 @@
+//! Timing checks for big colonies. Debug builds are far too slow to say
+//! anything useful, so these are ignored by default; run them with
+//! `cargo test --release --test perf -- --ignored --nocapture`.
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::config::GameConfig;
+use tiny_colony::pawn::{self, Attributes, Pawn};
+use tiny_colony::pawn_render::{self, PawnBatch};
+
+const PAWNS: usize = 10_000;
+const SIZE: i32 = 160;
+const TICKS: u32 = 100;
+
+const DRAWN_PAWNS: i32 = 50_000;
+/// One frame at 60 fps.
+const FRAME_BUDGET: Duration = Duration::from_micros(16_667);
+
+/// Open ground with a tree on every third tile each way and the stockpile in
+/// the middle.
+fn forest() -> String {
+    let mut ascii = String::new();
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            ascii.push(if x == SIZE / 2 && y == SIZE / 2 {
+                'S'
+            } else if x % 3 == 0 && y % 3 == 0 {
+                'T'
+            } else {
+                '.'
+            });
+        }
+        ascii.push('\n');
+    }
+    ascii
+}
+
+#[test]
+#[ignore = "timing check; run in release with --ignored"]
+fn ten_thousand_pawns_tick_within_budget() {
+    let mut h = Harness::from_ascii(&forest());
+    assert_eq!(h.spawn_pawns(PAWNS, SIZE / 2, SIZE / 2), PAWNS);
+
+    // the first ticks are spent with everyone picking a tree at once
+    h.run_ticks(10);
+
+    let start = Instant::now();
+    h.run_ticks(TICKS);
+    let per_tick = start.elapsed() / TICKS;
+
+    let budget = Duration::from_secs_f32(h.app.world().resource::<GameConfig>().tick_seconds());
+    println!("{PAWNS} pawns: {per_tick:?} per tick, budget {budget:?}");
+    assert!(
+        per_tick < budget,
+        "{per_tick:?} per tick is over the {budget:?} budget"
+    );
+}
+
+fn time_update(app: &mut App) -> Duration {
+    let start = Instant::now();
+    app.update();
+    start.elapsed()
+}
+
+#[test]
+#[ignore = "timing check; run in release with --ignored"]
+fn fifty_thousand_pawns_redraw_within_a_frame() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+        .init_asset::<Mesh>()
+        .init_asset::<ColorMaterial>()
+        .init_resource::<GameConfig>()
+        .add_systems(Update, pawn_render::update_pawn_batch);
+
+    let world = app.world_mut();
+    world.resource_scope(|world, mut meshes: Mut<Assets<Mesh>>| {
+        world.resource_scope(|world, mut materials: Mut<Assets<ColorMaterial>>| {
+            let mut commands = world.commands();
+            pawn_render::spawn_pawn_batch(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                Handle::default(),
+            );
+            for id in 0..DRAWN_PAWNS {
+                let at = IVec2::new(id % 250, id / 250);
+                pawn::spawn_pawn(&mut commands, id as u32, at, Attributes::default());
+            }
+        });
+    });
+    world.flush();
+
+    let built = time_update(&mut app);
+    assert_eq!(
+        app.world().resource::<PawnBatch>().positions.len(),
+        DRAWN_PAWNS as usize
+    );
+    let idle = time_update(&mut app);
+
+    // the worst case: every pawn takes a step in the same frame
+    let world = app.world_mut();
+    for mut pawn in world.query::<&mut Pawn>().iter_mut(world) {
+        pawn.x += 1;
+    }
+    let moved = time_update(&mut app);
+
+    println!(
+        "{DRAWN_PAWNS} pawns: built in {built:?}, {moved:?} when all move, {idle:?} when none do"
+    );
+    for took in [built, moved, idle] {
+        assert!(
+            took < FRAME_BUDGET,
+            "{took:?} is over the {FRAME_BUDGET:?} frame budget"
+        );
+    }
+}
@@ -0,0 +1,140 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::colony::Colony;
+use tiny_colony::config::GameConfig;
+use tiny_colony::events::EventLog;
+use tiny_colony::pawn::{Attributes, Pawn};
+use tiny_colony::population::{self, BED_WOOD, Population};
+use tiny_colony::world::{self, Tile, WorldMap};
+
+fn with_wanderers(wood: u32, beds: usize) -> Harness {
+    let mut h = Harness::from_ascii("S.....\n......\n......");
+    let world = h.app.world_mut();
+    let mut config = world.resource_mut::<GameConfig>();
+    config.wanderer_every_hours = 1;
+    // two silver a log, so it takes ten wood
+    config.join_wealth = 20;
+    config.beds = beds;
+    world.resource_mut::<Colony>().wood = wood;
+    h
+}
+
+fn pawn_ids(h: &mut Harness) -> Vec<u32> {
+    let world = h.app.world_mut();
+    let mut ids: Vec<u32> = world.query::<&Pawn>().iter(world).map(|pawn| pawn.id).collect();
+    ids.sort();
+    ids
+}
+
+fn pawn_count(h: &mut Harness) -> usize {
+    let world = h.app.world_mut();
+    world.query::<&Pawn>().iter(world).count()
+}
+
+fn logged(h: &Harness, text: &str) -> bool {
+    let log = h.app.world().resource::<EventLog>();
+    log.entries.iter().any(|entry| entry.contains(text))
+}
+
+fn run_hour(h: &mut Harness) {
+    let ticks = h.app.world().resource::<GameConfig>().ticks_per_hour;
+    h.run_ticks(ticks);
+}
+
+#[test]
+fn wanderers_join_a_wealthy_colony_until_the_beds_run_out() {
+    let mut h = with_wanderers(10, 2);
+    h.spawn_pawn(1, 1);
+
+    run_hour(&mut h);
+    assert_eq!(pawn_count(&mut h), 2);
+    assert!(logged(&h, "joined the colony as pawn 1"));
+
+    let newcomer = h.pawn_entity(1);
+    let attributes = *h.app.world().get::<Attributes>(newcomer).unwrap();
+    assert!((0.8..=1.2).contains(&attributes.speed));
+    assert!((0.8..=1.2).contains(&attributes.skill));
+
+    run_hour(&mut h);
+    assert_eq!(pawn_count(&mut h), 2);
+    assert!(logged(&h, "turned away: no free beds"));
+}
+
+#[test]
+fn nobody_comes_to_a_poor_colony() {
+    let mut h = with_wanderers(9, 10);
+    h.spawn_pawn(1, 1);
+
+    run_hour(&mut h);
+    run_hour(&mut h);
+    assert_eq!(pawn_count(&mut h), 1);
+    assert!(!logged(&h, "wanderer"));
+}
+
+#[test]
+fn population_is_sampled_every_hour() {
+    let mut h = with_wanderers(10, 3);
+    h.spawn_pawn(1, 1);
+
+    for _ in 0..3 {
+        run_hour(&mut h);
+    }
+    let population = h.app.world().resource::<Population>();
+    assert_eq!(Vec::from(population.history.clone()), vec![2, 3, 3]);
+    assert_eq!(population.current(), 3);
+}
+
+#[test]
+fn hours_run_in_one_frame_still_respect_the_beds() {
+    let mut h = with_wanderers(10, 2);
+    h.spawn_pawn(1, 1);
+
+    let ticks = h.app.world().resource::<GameConfig>().ticks_per_hour;
+    h.run_ticks(ticks * 3);
+    assert_eq!(pawn_ids(&mut h), vec![0, 1]);
+    assert!(logged(&h, "turned away: no free beds"));
+}
+
+#[test]
+fn pawn_ids_are_not_reused_after_a_death() {
+    let mut h = with_wanderers(10, 3);
+    h.spawn_pawn(1, 1);
+    h.spawn_pawn(2, 1);
+    let dead = h.pawn_entity(1);
+    h.app.world_mut().despawn(dead);
+
+    run_hour(&mut h);
+    assert_eq!(pawn_ids(&mut h), vec![0, 2]);
+    assert!(logged(&h, "joined the colony as pawn 2"));
+}
+
+#[test]
+fn built_beds_make_room_for_more() {
+    let mut h = with_wanderers(BED_WOOD + 10, 1);
+    h.spawn_pawn(1, 1);
+
+    let world = h.app.world_mut();
+    world.resource_scope(|world, mut map: Mut<WorldMap>| {
+        let mut colony = world.resource_mut::<Colony>();
+        assert!(population::build_bed(&mut map, &mut colony, IVec2::new(0, 2)).is_err());
+        population::build_bed(&mut map, &mut colony, IVec2::new(3, 0)).unwrap();
+        assert_eq!(colony.wood, 10);
+        assert_eq!(world::get(&map, 3, 0), Tile::Bed);
+    });
+
+    run_hour(&mut h);
+    assert_eq!(pawn_count(&mut h), 2);
+}
+
+#[test]
+fn any_kind_of_stock_counts_as_wealth() {
+    let mut h = with_wanderers(0, 2);
+    h.app.world_mut().resource_mut::<Colony>().medicine = 1;
+    h.spawn_pawn(1, 1);
+
+    run_hour(&mut h);
+    assert_eq!(pawn_count(&mut h), 2);
+}
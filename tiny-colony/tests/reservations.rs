@@ -0,0 +1,74 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::config::GameConfig;
+use tiny_colony::pawn::Task;
+use tiny_colony::sim::Reservations;
+
+#[test]
+fn despawning_a_pawn_releases_its_reservation() {
+    let mut h = Harness::from_ascii("S....T");
+    let pawn = h.spawn_pawn(0, 0);
+    h.run_ticks(1);
+    assert_eq!(h.reservations().reserved_tiles.len(), 1);
+
+    let entity = h.pawn_entity(pawn);
+    h.app.world_mut().despawn(entity);
+
+    assert!(h.reservations().reserved_tiles.is_empty());
+    assert!(h.reservations().by_pawn.is_empty());
+}
+
+#[test]
+fn changing_a_task_externally_releases_its_reservation() {
+    let mut h = Harness::from_ascii("S....T");
+    let pawn = h.spawn_pawn(2, 0);
+    h.run_ticks(1);
+
+    let entity = h.pawn_entity(pawn);
+    *h.app.world_mut().get_mut::<Task>(entity).unwrap() = Task::GoToStockpile;
+    h.run_ticks(1);
+
+    // the pawn walks home instead of keeping the tree locked
+    assert_eq!(h.task(pawn), Task::GoToStockpile);
+    assert!(h.reservations().reserved_tiles.is_empty());
+}
+
+#[test]
+fn stale_reservations_expire() {
+    let mut h = Harness::from_ascii("S....T");
+    h.app
+        .world_mut()
+        .resource_mut::<GameConfig>()
+        .reservation_timeout_ticks = 3;
+    let pawn = h.spawn_pawn(0, 0);
+
+    h.run_ticks(4);
+    assert_eq!(h.task(pawn), Task::GoToTree(IVec2::new(5, 0)));
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert!(h.reservations().reserved_tiles.is_empty());
+}
+
+#[test]
+fn leak_check_reports_orphaned_reservations() {
+    let mut h = Harness::from_ascii("S....T");
+    let pawn = h.spawn_pawn(0, 0);
+    h.run_ticks(1);
+
+    let entity = h.pawn_entity(pawn);
+    let tree = IVec2::new(5, 0);
+    let leaks = h.reservations().leaks(|_| Some(Task::GoToTree(tree)));
+    assert!(leaks.is_empty());
+
+    let ghost = h.app.world_mut().spawn_empty().id();
+    let mut reservations = h.app.world_mut().resource_mut::<Reservations>();
+    reservations.reserved_tiles.insert(IVec2::new(4, 0), ghost);
+
+    let leaks = reservations.leaks(|pawn| (pawn == entity).then_some(Task::GoToTree(tree)));
+    assert_eq!(leaks.len(), 1);
+    assert_eq!(leaks[0].pawn, ghost);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::Harness;
+use tiny_colony::config::GameConfig;
+use tiny_colony::pawn::Inventory;
+use tiny_colony::scenario::{Goal, GoalProgress, Outcome, Scenario};
+use tiny_colony::sim::Sim;
+
+fn deliver_wood(amount: u32, before_tick: Option<u64>) -> Scenario {
+    Scenario {
+        name: "test".to_string(),
+        map: None,
+        generated_size: Default::default(),
+        spawn_at: None,
+        pawn_count: 0,
+        wood: 0,
+        goals: vec![GoalProgress {
+            goal: Goal::DeliverWood {
+                amount,
+                before_tick,
+            },
+            met_at: None,
+        }],
+        delivered: Inventory::default(),
+        outcome: None,
+    }
+}
+
+fn run(scenario: Scenario, ticks: u32) -> Harness {
+    let mut h = Harness::from_ascii("S..T");
+    h.spawn_pawn(0, 0);
+    h.app.world_mut().insert_resource(scenario);
+    h.app.world_mut().resource_mut::<Sim>().pending_ticks += ticks;
+    h.app.update();
+    h
+}
+
+#[test]
+fn shipped_scenario_loads_and_applies() {
+    let scenario = Scenario::load("scenarios/clearing.ron").unwrap();
+    let mut config = GameConfig::default();
+    scenario.apply(&mut config).unwrap();
+    config.validate().unwrap();
+
+    assert_eq!((config.map_w, config.map_h), (24, 11));
+    assert_eq!(config.stockpile(), bevy::math::IVec2::new(12, 5));
+    assert_eq!(config.pawn_count, 12);
+}
+
+#[test]
+fn meeting_the_goal_wins_and_stops_the_sim() {
+    let h = run(deliver_wood(1, Some(20)), 30);
+
+    let scenario = h.app.world().resource::<Scenario>();
+    assert_eq!(scenario.outcome, Some(Outcome::Won));
+    assert_eq!(scenario.goals[0].met_at, Some(18));
+    assert_eq!(h.sim_ticks(), 18);
+    assert!(h.app.world().resource::<Sim>().paused);
+}
+
+#[test]
+fn missing_the_deadline_loses() {
+    let h = run(deliver_wood(1, Some(10)), 30);
+
+    let scenario = h.app.world().resource::<Scenario>();
+    assert_eq!(scenario.outcome, Some(Outcome::Lost));
+    assert_eq!(h.sim_ticks(), 10);
+}
+
+#[test]
+fn queued_ticks_are_dropped_once_the_scenario_ends() {
+    let mut h = run(deliver_wood(1, Some(20)), 100);
+    h.app.update();
+    h.app.update();
+
+    assert_eq!(h.sim_ticks(), 18);
+    assert_eq!(h.app.world().resource::<Sim>().pending_ticks, 0);
+}
+
+#[test]
+fn delivered_stone_counts_towards_stone_goals() {
+    let mut scenario = deliver_wood(0, None);
+    scenario.goals[0].goal = Goal::DeliverStone {
+        amount: 1,
+        before_tick: None,
+    };
+
+    let mut h = Harness::from_ascii("S..#");
+    h.spawn_pawn(0, 0);
+    h.designate_mine(3, 0);
+    h.app.world_mut().insert_resource(scenario);
+    h.app.world_mut().resource_mut::<Sim>().pending_ticks = 60;
+    h.app.update();
+    h.app.update();
+
+    let scenario = h.app.world().resource::<Scenario>();
+    assert_eq!(scenario.outcome, Some(Outcome::Won));
+    assert_eq!(scenario.delivered.stone, 1);
+    assert_eq!(scenario.delivered.wood, 0);
+}
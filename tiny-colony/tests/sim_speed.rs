@@ -0,0 +1,82 @@
+mod common;
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use common::Harness;
+use tiny_colony::config::GameConfig;
+use tiny_colony::console::{self, ConsoleCommand};
+use tiny_colony::sim::{MAX_SPEED, Sim};
+
+// a power-of-two tick length keeps the timer arithmetic exact
+const TICK_SECONDS: f32 = 0.125;
+
+// bevy clamps a frame's virtual delta to 250ms
+const SLOW_FRAME: Duration = Duration::from_millis(250);
+
+fn run_frame(h: &mut Harness, speed: f32) -> u64 {
+    h.app
+        .insert_resource(TimeUpdateStrategy::ManualDuration(SLOW_FRAME));
+    {
+        let mut sim = h.app.world_mut().resource_mut::<Sim>();
+        sim.tick = Timer::from_seconds(TICK_SECONDS, TimerMode::Repeating);
+        sim.paused = false;
+        sim.speed = speed;
+    }
+
+    let before = h.sim_ticks();
+    h.app.update();
+    h.sim_ticks() - before
+}
+
+#[test]
+fn slow_frames_run_every_tick_that_came_due() {
+    let mut h = Harness::from_ascii("S..T");
+
+    // a quarter second at 4x is one sim second
+    assert_eq!(run_frame(&mut h, 4.0), 8);
+}
+
+#[test]
+fn catch_up_is_capped_per_frame() {
+    let mut h = Harness::from_ascii("S..T");
+    h.app
+        .world_mut()
+        .resource_mut::<GameConfig>()
+        .max_ticks_per_frame = 10;
+
+    // 16x would be 32 ticks this frame
+    assert_eq!(run_frame(&mut h, 16.0), 10);
+}
+
+#[test]
+fn queued_ticks_are_spread_over_frames() {
+    let mut h = Harness::from_ascii("S..T");
+    h.app
+        .world_mut()
+        .resource_mut::<GameConfig>()
+        .max_ticks_per_frame = 10;
+    h.app.world_mut().resource_mut::<Sim>().pending_ticks = 25;
+
+    let mut per_frame = Vec::new();
+    for _ in 0..4 {
+        let before = h.sim_ticks();
+        h.app.update();
+        per_frame.push(h.sim_ticks() - before);
+    }
+    assert_eq!(per_frame, vec![10, 10, 5, 0]);
+}
+
+#[test]
+fn console_speed_must_be_finite_and_within_the_top_step() {
+    for speed in ["inf", "NaN", "1e30", "0", "-2"] {
+        let line = format!("speed {speed}");
+        assert!(console::parse_command(&line).is_err(), "{line} was accepted");
+    }
+    assert_eq!(
+        console::parse_command(&format!("speed {MAX_SPEED}")),
+        Ok(ConsoleCommand::Speed(MAX_SPEED))
+    );
+}
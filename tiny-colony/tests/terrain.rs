@@ -0,0 +1,85 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::pawn::Task;
+use tiny_colony::world::Tile;
+
+fn ticks_to_reach_tree(ascii: &str) -> u32 {
+    let mut h = Harness::from_ascii(ascii);
+    let pawn = h.spawn_pawn(1, 0);
+
+    let mut ticks = 0;
+    while !matches!(h.task(pawn), Task::Chop { .. }) {
+        h.run_ticks(1);
+        ticks += 1;
+        assert!(ticks < 100, "never reached the tree");
+    }
+    ticks
+}
+
+#[test]
+fn pawns_walk_round_rock() {
+    let mut h = Harness::from_ascii(
+        "......\n\
+         S.#.T.\n\
+         ..#...",
+    );
+    let pawn = h.spawn_pawn(1, 1);
+
+    for _ in 0..6 {
+        h.run_ticks(1);
+        let at = h.position(pawn);
+        assert_ne!(h.tile(at.x, at.y), Tile::Rock);
+    }
+    assert_eq!(h.position(pawn), IVec2::new(4, 1));
+    assert!(matches!(h.task(pawn), Task::Chop { .. }));
+}
+
+#[test]
+fn mud_and_shallow_water_slow_pawns_down() {
+    let ground = ticks_to_reach_tree("S.....T");
+    let mud = ticks_to_reach_tree("S.%%%%T");
+    let water = ticks_to_reach_tree("S.----T");
+    assert!(mud > ground);
+    assert!(water > mud);
+}
+
+#[test]
+fn deep_water_cuts_pawns_off() {
+    let mut h = Harness::from_ascii("S.~T");
+    let pawn = h.spawn_pawn(1, 0);
+
+    h.run_ticks(10);
+    assert_eq!(h.position(pawn), IVec2::new(1, 0));
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert!(h.reservations().reserved_tiles.is_empty());
+}
+
+#[test]
+fn pawns_pass_over_trees_they_cannot_reach() {
+    let mut h = Harness::from_ascii("T....S.~T");
+    let pawn = h.spawn_pawn(6, 0);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToTree(IVec2::new(0, 0)));
+}
+
+#[test]
+fn pawns_give_up_trees_once_the_way_is_cut_off() {
+    let mut h = Harness::from_ascii("S.....T");
+    let pawn = h.spawn_pawn(1, 0);
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToTree(IVec2::new(6, 0)));
+
+    h.set_tile(4, 0, Tile::DeepWater);
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert!(h.reservations().reserved_tiles.is_empty());
+
+    // and picks it back up once there's a way again
+    h.set_tile(4, 0, Tile::ShallowWater);
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToTree(IVec2::new(6, 0)));
+}
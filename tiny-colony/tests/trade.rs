@@ -0,0 +1,176 @@
+mod common;
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::colony::{Colony, Item};
+use tiny_colony::config::GameConfig;
+use tiny_colony::events::EventLog;
+use tiny_colony::pawn::Inventory;
+use tiny_colony::trade::{self, Caravan, CaravanState, TRADE_TICKS};
+use tiny_colony::world::Path;
+
+fn caravan(silver: u32, stock: &[(Item, u32)]) -> Caravan {
+    Caravan {
+        x: 0,
+        y: 0,
+        exit: IVec2::ZERO,
+        state: CaravanState::Trading {
+            ticks_left: TRADE_TICKS,
+        },
+        effort: 0.0,
+        path: Path::default(),
+        silver,
+        stock: stock.iter().copied().collect::<BTreeMap<_, _>>(),
+    }
+}
+
+fn caravans(h: &mut Harness) -> Vec<Caravan> {
+    let world = h.app.world_mut();
+    world.query::<&Caravan>().iter(world).cloned().collect()
+}
+
+fn logged(h: &Harness, text: &str) -> bool {
+    let log = h.app.world().resource::<EventLog>();
+    log.entries.iter().any(|entry| entry.contains(text))
+}
+
+#[test]
+fn prices_fall_as_supply_grows() {
+    for item in [Item::Wood, Item::Planks, Item::Medicine, Item::Steel] {
+        assert!(trade::value(item, 0) > trade::value(item, 20));
+        assert!(trade::value(item, 20) > trade::value(item, 60));
+        assert_eq!(trade::value(item, 20), trade::base_value(item));
+        assert!(trade::buy_price(item, 10) > trade::sell_price(item, 10));
+    }
+}
+
+#[test]
+fn wealth_counts_everything_at_base_value() {
+    let colony = Colony(Inventory {
+        wood: 10,
+        steel: 2,
+        silver: 7,
+        ..default()
+    });
+    let expected = 10 * trade::base_value(Item::Wood) + 2 * trade::base_value(Item::Steel) + 7;
+    assert_eq!(trade::wealth(&colony), expected);
+}
+
+#[test]
+fn selling_pays_silver_and_lowers_the_next_offer() {
+    let mut caravan = caravan(500, &[]);
+    let mut colony = Colony(Inventory {
+        wood: 30,
+        ..default()
+    });
+
+    let first = caravan.quote_sell(Item::Wood, 1);
+    let paid = trade::sell(&mut caravan, &mut colony, Item::Wood, 10).unwrap();
+    assert!(paid >= first);
+    assert_eq!(colony.wood, 20);
+    assert_eq!(colony.silver, paid);
+    assert_eq!(caravan.silver, 500 - paid);
+    assert_eq!(caravan.amount(Item::Wood), 10);
+    assert!(caravan.quote_sell(Item::Wood, 1) < first);
+
+    assert!(trade::sell(&mut caravan, &mut colony, Item::Wood, 21).is_err());
+    assert_eq!(colony.wood, 20);
+}
+
+#[test]
+fn buying_needs_enough_silver() {
+    let mut caravan = caravan(0, &[(Item::Medicine, 5)]);
+    let mut colony = Colony(Inventory {
+        silver: 100,
+        ..default()
+    });
+
+    let cost = caravan.quote_buy(Item::Medicine, 2);
+    assert!(cost > colony.silver);
+    assert!(trade::buy(&mut caravan, &mut colony, Item::Medicine, 2).is_err());
+    assert_eq!(colony.silver, 100);
+    assert_eq!(caravan.amount(Item::Medicine), 5);
+
+    let cost = trade::buy(&mut caravan, &mut colony, Item::Medicine, 1).unwrap();
+    assert_eq!(colony.medicine, 1);
+    assert_eq!(colony.silver, 100 - cost);
+    assert_eq!(caravan.silver, cost);
+    assert_eq!(caravan.amount(Item::Medicine), 4);
+    assert!(trade::buy(&mut caravan, &mut colony, Item::Silver, 1).is_err());
+}
+
+#[test]
+fn caravans_walk_in_trade_and_leave() {
+    let mut h = Harness::from_ascii(
+        "
+        ..........
+        ..........
+        ....S.....
+        ..........
+        ..........
+        ",
+    );
+    h.app
+        .world_mut()
+        .resource_mut::<GameConfig>()
+        .caravan_every_hours = 1;
+
+    h.run_ticks(9);
+    assert!(caravans(&mut h).is_empty());
+
+    h.run_ticks(1);
+    let arrived = caravans(&mut h);
+    assert_eq!(arrived.len(), 1);
+    let at = arrived[0].pos();
+    assert!(at.x == 0 || at.y == 0 || at.x == 9 || at.y == 4);
+    assert!(logged(&h, "trade caravan appeared"));
+
+    // so nobody new turns up once this one has gone
+    h.app
+        .world_mut()
+        .resource_mut::<GameConfig>()
+        .caravan_every_hours = 0;
+
+    let mut ticks = 0;
+    while !caravans(&mut h)[0].is_trading() {
+        h.run_ticks(1);
+        ticks += 1;
+        assert!(ticks < 30, "never reached the stockpile");
+    }
+    let trading = &caravans(&mut h)[0];
+    assert!((trading.pos() - IVec2::new(4, 2)).abs().element_sum() <= 1);
+    assert!(logged(&h, "reached the stockpile"));
+
+    h.run_ticks(TRADE_TICKS - 1);
+    assert!(caravans(&mut h)[0].is_trading());
+    h.run_ticks(1);
+    assert_eq!(caravans(&mut h)[0].state, CaravanState::Leaving);
+
+    h.run_ticks(30);
+    assert!(caravans(&mut h).is_empty());
+    assert!(logged(&h, "moved on"));
+}
+
+#[test]
+fn no_caravan_comes_when_the_stockpile_is_cut_off() {
+    let mut h = Harness::from_ascii(
+        "
+        ..........
+        ...~~~....
+        ...~S~....
+        ...~~~....
+        ..........
+        ",
+    );
+    h.app
+        .world_mut()
+        .resource_mut::<GameConfig>()
+        .caravan_every_hours = 1;
+
+    h.run_ticks(50);
+    assert!(caravans(&mut h).is_empty());
+    assert!(!logged(&h, "trade caravan appeared"));
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::Harness;
+use tiny_colony::pawn::Task;
+use tiny_colony::rng::SimRng;
+use tiny_colony::weather::{self, Season, Weather, WeatherKind};
+use tiny_colony::world::{self, Tile, WorldTrees};
+
+#[test]
+fn seasons_follow_the_day() {
+    assert_eq!(Season::of_day(1, 5), Season::Spring);
+    assert_eq!(Season::of_day(5, 5), Season::Spring);
+    assert_eq!(Season::of_day(6, 5), Season::Summer);
+    assert_eq!(Season::of_day(16, 5), Season::Winter);
+    assert_eq!(Season::of_day(21, 5), Season::Spring);
+}
+
+#[test]
+fn it_only_snows_in_winter() {
+    let mut rng = SimRng::new(7);
+    let mut weather = Weather::default();
+    for season in [Season::Spring, Season::Summer, Season::Autumn] {
+        for _ in 0..200 {
+            weather.roll(season, &mut rng);
+            assert_ne!(weather.kind, WeatherKind::Snow);
+        }
+    }
+
+    let snowed = (0..200).any(|_| {
+        weather.roll(Season::Winter, &mut rng);
+        weather.kind == WeatherKind::Snow
+    });
+    assert!(snowed);
+}
+
+#[test]
+fn snow_slows_pawns_down() {
+    let map = "S.........T";
+
+    let mut clear = Harness::from_ascii(map);
+    clear.spawn_pawn(1, 0);
+    clear.run_ticks(12);
+    assert!(matches!(clear.task(0), Task::Chop { .. }));
+
+    let mut snowy = Harness::from_ascii(map);
+    snowy.app.world_mut().resource_mut::<Weather>().kind = WeatherKind::Snow;
+    snowy.spawn_pawn(1, 0);
+    snowy.run_ticks(12);
+    assert!(matches!(snowy.task(0), Task::GoToTree(_)));
+}
+
+#[test]
+fn trees_spread_onto_empty_ground() {
+    let mut map = world::parse_ascii_map("TTTTT\nTT.TT\nTTTTS").unwrap();
+    let mut trees = WorldTrees::from_map(&map);
+    let mut rng = SimRng::new(3);
+
+    let grown = (0..1000)
+        .find_map(|_| weather::grow_trees(&mut map, &mut trees, &mut rng, 1.0))
+        .expect("nothing grew");
+
+    assert_eq!(grown.x, 2);
+    assert_eq!(grown.y, 1);
+    assert_eq!(world::get(&map, 2, 1), Tile::Tree);
+    assert_eq!(world::get(&map, 4, 0), Tile::Stockpile);
+    assert!(trees.0.contains(&grown));
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use bevy::prelude::*;
+
+use common::Harness;
+use tiny_colony::events::EventLog;
+use tiny_colony::pawn::Task;
+use tiny_colony::world::Tile;
+
+#[test]
+fn pawn_chops_hauls_and_drops_off_wood() {
+    let mut h = Harness::from_ascii("S..T");
+    let pawn = h.spawn_pawn(0, 0);
+    let tree = IVec2::new(3, 0);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToTree(tree));
+
+    // three steps to reach the tree
+    h.run_ticks(3);
+    assert_eq!(h.task(pawn), Task::Chop { at: tree, progress: 0 });
+
+    // ten ticks of chopping fells it
+    h.run_ticks(9);
+    assert_eq!(h.task(pawn), Task::Chop { at: tree, progress: 9 });
+    assert_eq!(h.tile(3, 0), Tile::Tree);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::GoToStockpile);
+    assert_eq!(h.tile(3, 0), Tile::Ground);
+    assert_eq!(h.inventory(pawn).wood, 1);
+    assert_eq!(h.colony().wood, 0);
+    // the last tree is down, though the wood is still on its way in
+    let log = h.app.world().resource::<EventLog>();
+    assert!(log.entries.iter().any(|entry| entry.contains("Forest depleted")));
+
+    // three steps back, then the drop-off itself
+    h.run_ticks(3);
+    assert_eq!(h.task(pawn), Task::DropOff);
+
+    h.run_ticks(1);
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert_eq!(h.inventory(pawn).wood, 0);
+    assert_eq!(h.colony().wood, 1);
+}
+
+#[test]
+fn pawns_reserve_different_trees() {
+    let mut h = Harness::from_ascii("T.S.T");
+    let a = h.spawn_pawn(1, 0);
+    let b = h.spawn_pawn(3, 0);
+
+    h.run_ticks(1);
+
+    let (Task::GoToTree(at_a), Task::GoToTree(at_b)) = (h.task(a), h.task(b)) else {
+        panic!("both pawns should be heading to a tree");
+    };
+    assert_ne!(at_a, at_b);
+    assert_eq!(h.reservations().reserved_tiles.len(), 2);
+}
+
+#[test]
+fn felling_a_tree_releases_its_reservation() {
+    let mut h = Harness::from_ascii("ST");
+    let pawn = h.spawn_pawn(0, 0);
+    let tree = IVec2::new(1, 0);
+    let entity = h.pawn_entity(pawn);
+
+    h.run_ticks(1);
+    assert_eq!(h.reservations().reserved_tiles.get(&tree), Some(&entity));
+
+    // one step, then ten ticks of chopping
+    h.run_ticks(11);
+    assert_eq!(h.task(pawn), Task::GoToStockpile);
+    assert!(h.reservations().reserved_tiles.is_empty());
+}
+
+#[test]
+fn chopping_a_vanished_tree_releases_its_reservation() {
+    let mut h = Harness::from_ascii("ST");
+    let pawn = h.spawn_pawn(0, 0);
+
+    h.run_ticks(3);
+    assert!(matches!(h.task(pawn), Task::Chop { .. }));
+    assert_eq!(h.reservations().reserved_tiles.len(), 1);
+
+    h.set_tile(1, 0, Tile::Ground);
+    h.run_ticks(1);
+
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert!(h.reservations().reserved_tiles.is_empty());
+    assert_eq!(h.inventory(pawn).wood, 0);
+}
+
+#[test]
+fn idle_pawns_stay_idle_without_trees() {
+    let mut h = Harness::from_ascii("S..");
+    let pawn = h.spawn_pawn(0, 0);
+
+    h.run_ticks(20);
+
+    assert_eq!(h.task(pawn), Task::Idle);
+    assert_eq!(h.colony().wood, 0);
+}
@@ -0,0 +1,166 @@
+use bevy::prelude::*;
+
+use tiny_colony::config::GameConfig;
+use tiny_colony::world::{self, CHUNK_SIZE, Path, Tile, WorldMap};
+
+#[test]
+fn chunk_count_covers_partial_chunks() {
+    let map = WorldMap::new(CHUNK_SIZE * 2 + 1, CHUNK_SIZE);
+    assert_eq!(map.chunk_count(), IVec2::new(3, 1));
+}
+
+#[test]
+fn setting_a_tile_marks_only_its_chunk_dirty() {
+    let mut map = WorldMap::new(CHUNK_SIZE * 4, CHUNK_SIZE * 4);
+
+    world::set(&mut map, CHUNK_SIZE + 3, CHUNK_SIZE * 2, Tile::Tree);
+    world::set(&mut map, CHUNK_SIZE + 5, CHUNK_SIZE * 2 + 1, Tile::Ground);
+
+    assert_eq!(map.dirty_chunks.len(), 1);
+    assert!(map.dirty_chunks.contains(&IVec2::new(1, 2)));
+}
+
+#[test]
+fn ascii_maps_cover_every_terrain() {
+    let map = world::parse_ascii_map("-~#:%\n.TS..").unwrap();
+
+    let top: Vec<Tile> = (0..5).map(|x| world::get(&map, x, 1)).collect();
+    assert_eq!(
+        top,
+        vec![
+            Tile::ShallowWater,
+            Tile::DeepWater,
+            Tile::Rock,
+            Tile::Sand,
+            Tile::Mud
+        ]
+    );
+    assert!(!Tile::DeepWater.is_walkable());
+    assert!(!Tile::Rock.is_walkable());
+    assert!(Tile::ShallowWater.move_cost() > Tile::Sand.move_cost());
+}
+
+#[test]
+fn generated_world_has_varied_terrain() {
+    let config = GameConfig::default();
+    let map = world::build_world(&config);
+
+    for tile in [
+        Tile::ShallowWater,
+        Tile::DeepWater,
+        Tile::Rock,
+        Tile::Sand,
+        Tile::Mud,
+    ] {
+        assert!(map.tiles.contains(&tile), "no {tile:?} generated");
+    }
+    assert_eq!(
+        world::get(&map, config.stockpile().x, config.stockpile().y),
+        Tile::Stockpile
+    );
+}
+
+#[test]
+fn stockpile_follows_the_map_size_unless_placed() {
+    let args = ["--map-w", "16", "--map-h", "10"].map(String::from);
+    let config = GameConfig::from_args(args).unwrap();
+    assert_eq!(config.stockpile(), IVec2::new(8, 5));
+    let map = world::build_world(&config);
+    assert_eq!(world::get(&map, 8, 5), Tile::Stockpile);
+
+    let args = ["--map-w", "16", "--stockpile-x", "3"].map(String::from);
+    let config = GameConfig::from_args(args).unwrap();
+    assert_eq!(config.stockpile().x, 3);
+
+    let args = ["--map-w", "16", "--stockpile-x", "20"].map(String::from);
+    assert!(GameConfig::from_args(args).is_err());
+}
+
+#[test]
+fn steps_go_round_what_cannot_be_walked() {
+    let map = world::parse_ascii_map("....\n.#..\n.~..").unwrap();
+
+    // straight ahead when the way is clear
+    assert_eq!(
+        world::next_step(&map, IVec2::new(0, 2), IVec2::new(3, 2)),
+        Some(IVec2::new(1, 2))
+    );
+    // round the rock rather than into it
+    assert_eq!(
+        world::next_step(&map, IVec2::new(0, 1), IVec2::new(3, 1)),
+        Some(IVec2::new(0, 2))
+    );
+    // nowhere to go when the target is walled off
+    let walled = world::parse_ascii_map(".#.").unwrap();
+    assert_eq!(
+        world::next_step(&walled, IVec2::new(0, 0), IVec2::new(2, 0)),
+        None
+    );
+}
+
+#[test]
+fn routes_weigh_the_terrain_they_cross() {
+    let walk = |ascii: &str, from: IVec2, to: IVec2| {
+        let map = world::parse_ascii_map(ascii).unwrap();
+        let (mut at, mut path, mut tiles) = (from, Path::default(), Vec::new());
+        while let Some(next) = path.next_step(&map, at, to) {
+            at = next;
+            tiles.push(world::get(&map, at.x, at.y));
+        }
+        tiles
+    };
+    let (from, to) = (IVec2::new(0, 1), IVec2::new(4, 1));
+
+    // two steps out of the way keep the feet dry...
+    for ascii in [".....\n.---.\n.....", ".....\n.%%%.\n....."] {
+        let tiles = walk(ascii, from, to);
+        assert_eq!(tiles.len(), 6);
+        assert!(tiles.iter().all(|&tile| tile == Tile::Ground), "{tiles:?}");
+    }
+    // ...but aren't worth it to skip a single puddle
+    let tiles = walk(".....\n..-..\n.....", from, to);
+    assert_eq!(tiles.len(), 4);
+    assert!(tiles.contains(&Tile::ShallowWater));
+}
+
+#[test]
+fn paths_lead_out_of_pockets_without_doubling_back() {
+    // the pawn starts in a pocket open to the west with its target to the east
+    let map = world::parse_ascii_map(
+        "
+        ..........
+        .#####....
+        .....#.T..
+        .#####....
+        ..........
+        ",
+    )
+    .unwrap();
+    let (mut at, to) = (IVec2::new(3, 2), IVec2::new(7, 2));
+
+    let mut path = Path::default();
+    let mut visited = vec![at];
+    while let Some(next) = path.next_step(&map, at, to) {
+        assert_eq!((next - at).abs().element_sum(), 1);
+        assert!(!visited.contains(&next), "stepped back onto {next}");
+        visited.push(next);
+        at = next;
+    }
+    assert_eq!(at, to);
+    assert_eq!(visited.len() - 1, 14);
+}
+
+#[test]
+fn paths_are_worked_out_again_when_the_map_changes() {
+    let mut map = world::parse_ascii_map("......").unwrap();
+    let (from, to) = (IVec2::new(0, 0), IVec2::new(5, 0));
+
+    let mut path = Path::default();
+    assert_eq!(path.next_step(&map, from, to), Some(IVec2::new(1, 0)));
+
+    world::set(&mut map, 3, 0, Tile::Rock);
+    assert_eq!(path.next_step(&map, from, to), None);
+
+    world::set(&mut map, 3, 0, Tile::Ground);
+    assert_eq!(path.next_step(&map, from, to), Some(IVec2::new(1, 0)));
+}